@@ -0,0 +1,204 @@
+//! Consensus logic and parameters.
+
+use std::collections::BTreeMap;
+
+use crate::constants::{mainnet, regtest, testnet};
+
+/// The kind of network a set of [`Parameters`] describes.
+///
+/// Unlike [`Parameters`], this is a plain enum that can be stored, compared, and
+/// passed across FFI or config boundaries without requiring a generic parameter.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum NetworkType {
+    /// Zcash mainnet.
+    Mainnet,
+    /// Zcash testnet.
+    Testnet,
+    /// The `zcashd`/`komodod` local-testing network.
+    Regtest,
+}
+
+/// A selectable network, implementing [`Parameters`] by dispatching to the
+/// `constants::{mainnet, testnet, regtest}` modules.
+///
+/// Unlike hard-coding a network via its `constants` module path, a [`Network`]
+/// value can be chosen at runtime (for example, from a config file), which is a
+/// precondition for a single binary that can act as a node on more than one
+/// network.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Network {
+    /// Zcash mainnet.
+    Mainnet,
+    /// Zcash testnet.
+    Testnet,
+    /// The `zcashd`/`komodod` local-testing network.
+    Regtest,
+}
+
+impl Network {
+    /// Returns the [`NetworkType`] discriminant for this network.
+    pub fn network_type(&self) -> NetworkType {
+        match self {
+            Network::Mainnet => NetworkType::Mainnet,
+            Network::Testnet => NetworkType::Testnet,
+            Network::Regtest => NetworkType::Regtest,
+        }
+    }
+}
+
+/// Network-specific consensus parameters.
+///
+/// Implementing this trait (rather than hard-coding a `constants::*` module path)
+/// lets callers write code that is generic over the network, or that switches
+/// networks at runtime via a [`Network`] value.
+pub trait Parameters {
+    /// Returns the HRP for a Bech32-encoded [`ExtendedSpendingKey`] on this network.
+    ///
+    /// [`ExtendedSpendingKey`]: crate::zip32::ExtendedSpendingKey
+    fn hrp_sapling_extended_spending_key(&self) -> &'static str;
+
+    /// Returns the HRP for a Bech32-encoded [`ExtendedFullViewingKey`] on this network.
+    ///
+    /// [`ExtendedFullViewingKey`]: crate::zip32::ExtendedFullViewingKey
+    fn hrp_sapling_extended_full_viewing_key(&self) -> &'static str;
+
+    /// Returns the HRP for a Bech32-encoded [`PaymentAddress`] on this network.
+    ///
+    /// [`PaymentAddress`]: crate::sapling::PaymentAddress
+    fn hrp_sapling_payment_address(&self) -> &'static str;
+
+    /// Returns the Base58Check prefix for a [`TransparentAddress::PublicKey`] on this network.
+    ///
+    /// [`TransparentAddress::PublicKey`]: crate::legacy::TransparentAddress::PublicKey
+    fn b58_pubkey_address_prefix(&self) -> [u8; 1];
+
+    /// Returns the Base58Check prefix for a [`TransparentAddress::Script`] on this network.
+    ///
+    /// [`TransparentAddress::Script`]: crate::legacy::TransparentAddress::Script
+    fn b58_script_address_prefix(&self) -> [u8; 1];
+
+    /// Returns the [SLIP 44] coin type for this network.
+    ///
+    /// [SLIP 44]: https://github.com/satoshilabs/slips/blob/master/slip-0044.md
+    fn coin_type(&self) -> u32;
+}
+
+impl Parameters for Network {
+    fn hrp_sapling_extended_spending_key(&self) -> &'static str {
+        match self {
+            Network::Mainnet => mainnet::HRP_SAPLING_EXTENDED_SPENDING_KEY,
+            Network::Testnet => testnet::HRP_SAPLING_EXTENDED_SPENDING_KEY,
+            Network::Regtest => regtest::HRP_SAPLING_EXTENDED_SPENDING_KEY,
+        }
+    }
+
+    fn hrp_sapling_extended_full_viewing_key(&self) -> &'static str {
+        match self {
+            Network::Mainnet => mainnet::HRP_SAPLING_EXTENDED_FULL_VIEWING_KEY,
+            Network::Testnet => testnet::HRP_SAPLING_EXTENDED_FULL_VIEWING_KEY,
+            Network::Regtest => regtest::HRP_SAPLING_EXTENDED_FULL_VIEWING_KEY,
+        }
+    }
+
+    fn hrp_sapling_payment_address(&self) -> &'static str {
+        match self {
+            Network::Mainnet => mainnet::HRP_SAPLING_PAYMENT_ADDRESS,
+            Network::Testnet => testnet::HRP_SAPLING_PAYMENT_ADDRESS,
+            Network::Regtest => regtest::HRP_SAPLING_PAYMENT_ADDRESS,
+        }
+    }
+
+    fn b58_pubkey_address_prefix(&self) -> [u8; 1] {
+        match self {
+            Network::Mainnet => mainnet::B58_PUBKEY_ADDRESS_PREFIX,
+            Network::Testnet => testnet::B58_PUBKEY_ADDRESS_PREFIX,
+            Network::Regtest => regtest::B58_PUBKEY_ADDRESS_PREFIX,
+        }
+    }
+
+    fn b58_script_address_prefix(&self) -> [u8; 1] {
+        match self {
+            Network::Mainnet => mainnet::B58_SCRIPT_ADDRESS_PREFIX,
+            Network::Testnet => testnet::B58_SCRIPT_ADDRESS_PREFIX,
+            Network::Regtest => regtest::B58_SCRIPT_ADDRESS_PREFIX,
+        }
+    }
+
+    fn coin_type(&self) -> u32 {
+        match self {
+            Network::Mainnet => mainnet::COIN_TYPE,
+            Network::Testnet => testnet::COIN_TYPE,
+            Network::Regtest => regtest::COIN_TYPE,
+        }
+    }
+}
+
+/// The network upgrades whose activation height can be set on a [`RegtestParameters`].
+///
+/// This mirrors the subset of `zebra_chain::parameters::NetworkUpgrade` that has a
+/// configurable activation height on `zcashd`/`komodod` regtest.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum NetworkUpgrade {
+    Overwinter,
+    Sapling,
+    Blossom,
+    Heartwood,
+    Canopy,
+    Nu5,
+}
+
+/// Regtest consensus parameters, with network-upgrade activation heights that can be
+/// set programmatically instead of being hard-coded.
+///
+/// Because regtest blocks are only ever mined locally, there is no single "correct"
+/// set of activation heights: integration tests want to activate upgrades at chosen
+/// heights, so that they can exercise upgrade-specific consensus rules without mining
+/// hundreds of thousands of blocks.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RegtestParameters {
+    activation_heights: BTreeMap<NetworkUpgrade, u32>,
+}
+
+impl RegtestParameters {
+    /// Returns regtest parameters with no network upgrades activated.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets `upgrade`'s activation height to `height`, returning `self` for chaining.
+    pub fn with_activation_height(mut self, upgrade: NetworkUpgrade, height: u32) -> Self {
+        self.activation_heights.insert(upgrade, height);
+        self
+    }
+
+    /// Returns the configured activation height for `upgrade`, if any.
+    pub fn activation_height(&self, upgrade: NetworkUpgrade) -> Option<u32> {
+        self.activation_heights.get(&upgrade).copied()
+    }
+}
+
+impl Parameters for RegtestParameters {
+    fn hrp_sapling_extended_spending_key(&self) -> &'static str {
+        regtest::HRP_SAPLING_EXTENDED_SPENDING_KEY
+    }
+
+    fn hrp_sapling_extended_full_viewing_key(&self) -> &'static str {
+        regtest::HRP_SAPLING_EXTENDED_FULL_VIEWING_KEY
+    }
+
+    fn hrp_sapling_payment_address(&self) -> &'static str {
+        regtest::HRP_SAPLING_PAYMENT_ADDRESS
+    }
+
+    fn b58_pubkey_address_prefix(&self) -> [u8; 1] {
+        regtest::B58_PUBKEY_ADDRESS_PREFIX
+    }
+
+    fn b58_script_address_prefix(&self) -> [u8; 1] {
+        regtest::B58_SCRIPT_ADDRESS_PREFIX
+    }
+
+    fn coin_type(&self) -> u32 {
+        regtest::COIN_TYPE
+    }
+}
@@ -0,0 +1,47 @@
+//! Constants for the Zcash main network.
+//!
+//! These are re-exported from the central [`constants`](super) table, keyed by
+//! [`NetworkType::Mainnet`](crate::consensus::NetworkType::Mainnet), so this
+//! module can't drift from the other networks' constants.
+
+use crate::consensus::NetworkType::Mainnet;
+
+/// The mainnet coin type for ZEC, as defined by [SLIP 44].
+///
+/// [SLIP 44]: https://github.com/satoshilabs/slips/blob/master/slip-0044.md
+pub const COIN_TYPE: u32 = super::coin_type(Mainnet);
+
+/// The HRP for a Bech32-encoded mainnet [`ExtendedSpendingKey`].
+///
+/// Defined in [ZIP 32].
+///
+/// [`ExtendedSpendingKey`]: crate::zip32::ExtendedSpendingKey
+/// [ZIP 32]: https://github.com/zcash/zips/blob/master/zip-0032.rst
+pub const HRP_SAPLING_EXTENDED_SPENDING_KEY: &str = super::hrp_sapling_extended_spending_key(Mainnet);
+
+/// The HRP for a Bech32-encoded mainnet [`ExtendedFullViewingKey`].
+///
+/// Defined in [ZIP 32].
+///
+/// [`ExtendedFullViewingKey`]: crate::zip32::ExtendedFullViewingKey
+/// [ZIP 32]: https://github.com/zcash/zips/blob/master/zip-0032.rst
+pub const HRP_SAPLING_EXTENDED_FULL_VIEWING_KEY: &str =
+    super::hrp_sapling_extended_full_viewing_key(Mainnet);
+
+/// The HRP for a Bech32-encoded mainnet [`PaymentAddress`].
+///
+/// Defined in section 5.6.4 of the [Zcash Protocol Specification].
+///
+/// [`PaymentAddress`]: crate::sapling::PaymentAddress
+/// [Zcash Protocol Specification]: https://github.com/zcash/zips/blob/master/protocol/protocol.pdf
+pub const HRP_SAPLING_PAYMENT_ADDRESS: &str = super::hrp_sapling_payment_address(Mainnet);
+
+/// The prefix for a Base58Check-encoded mainnet [`TransparentAddress::PublicKey`].
+///
+/// [`TransparentAddress::PublicKey`]: crate::legacy::TransparentAddress::PublicKey
+pub const B58_PUBKEY_ADDRESS_PREFIX: [u8; 1] = super::b58_pubkey_address_prefix(Mainnet);
+
+/// The prefix for a Base58Check-encoded mainnet [`TransparentAddress::Script`].
+///
+/// [`TransparentAddress::Script`]: crate::legacy::TransparentAddress::Script
+pub const B58_SCRIPT_ADDRESS_PREFIX: [u8; 1] = super::b58_script_address_prefix(Mainnet);
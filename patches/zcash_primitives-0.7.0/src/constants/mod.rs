@@ -0,0 +1,77 @@
+//! Network-specific constants.
+//!
+//! The per-network HRP and Base58Check prefix values live in a single table here,
+//! keyed by [`NetworkType`]. The `mainnet`, `testnet`, and `regtest` submodules
+//! re-export from this table under their historical `pub const` names, so
+//! existing callers that hard-code a `constants::<network>::CONST` path keep
+//! working, while new code can use the table directly to iterate over networks
+//! generically and without risking the per-network copies drifting apart.
+
+pub mod mainnet;
+pub mod regtest;
+pub mod testnet;
+
+use crate::consensus::NetworkType;
+
+/// Returns the [SLIP 44] coin type for `network`.
+///
+/// [SLIP 44]: https://github.com/satoshilabs/slips/blob/master/slip-0044.md
+pub const fn coin_type(network: NetworkType) -> u32 {
+    match network {
+        NetworkType::Mainnet => 133,
+        NetworkType::Testnet | NetworkType::Regtest => 1,
+    }
+}
+
+/// Returns the HRP for a Bech32-encoded [`ExtendedSpendingKey`] on `network`.
+///
+/// [`ExtendedSpendingKey`]: crate::zip32::ExtendedSpendingKey
+pub const fn hrp_sapling_extended_spending_key(network: NetworkType) -> &'static str {
+    match network {
+        NetworkType::Mainnet => "secret-extended-key-main",
+        NetworkType::Testnet => "secret-extended-key-test",
+        NetworkType::Regtest => "secret-extended-key-regtest",
+    }
+}
+
+/// Returns the HRP for a Bech32-encoded [`ExtendedFullViewingKey`] on `network`.
+///
+/// [`ExtendedFullViewingKey`]: crate::zip32::ExtendedFullViewingKey
+pub const fn hrp_sapling_extended_full_viewing_key(network: NetworkType) -> &'static str {
+    match network {
+        NetworkType::Mainnet => "zxviewmainsapling",
+        NetworkType::Testnet => "zxviewtestsapling",
+        NetworkType::Regtest => "zxviewregtestsapling",
+    }
+}
+
+/// Returns the HRP for a Bech32-encoded [`PaymentAddress`] on `network`.
+///
+/// [`PaymentAddress`]: crate::sapling::PaymentAddress
+pub const fn hrp_sapling_payment_address(network: NetworkType) -> &'static str {
+    match network {
+        NetworkType::Mainnet => "zs",
+        NetworkType::Testnet => "ztestsapling",
+        NetworkType::Regtest => "zregtestsapling",
+    }
+}
+
+/// Returns the Base58Check prefix for a [`TransparentAddress::PublicKey`] on `network`.
+///
+/// [`TransparentAddress::PublicKey`]: crate::legacy::TransparentAddress::PublicKey
+pub const fn b58_pubkey_address_prefix(network: NetworkType) -> [u8; 1] {
+    match network {
+        NetworkType::Mainnet => [0x1c],
+        NetworkType::Testnet | NetworkType::Regtest => [0],
+    }
+}
+
+/// Returns the Base58Check prefix for a [`TransparentAddress::Script`] on `network`.
+///
+/// [`TransparentAddress::Script`]: crate::legacy::TransparentAddress::Script
+pub const fn b58_script_address_prefix(network: NetworkType) -> [u8; 1] {
+    match network {
+        NetworkType::Mainnet => [0x1d],
+        NetworkType::Testnet | NetworkType::Regtest => [5],
+    }
+}
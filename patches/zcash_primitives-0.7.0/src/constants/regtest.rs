@@ -0,0 +1,54 @@
+//! Constants for the `zcashd`/`komodod` regtest (local-testing) network.
+//!
+//! These are re-exported from the central [`constants`](super) table, keyed by
+//! [`NetworkType::Regtest`](crate::consensus::NetworkType::Regtest), so this
+//! module can't drift from the other networks' constants.
+
+use crate::consensus::NetworkType::Regtest;
+
+/// The regtest coin type for ZEC, as defined by [SLIP 44].
+///
+/// Regtest reuses the testnet coin type, since it is never used on a public
+/// chain.
+///
+/// [SLIP 44]: https://github.com/satoshilabs/slips/blob/master/slip-0044.md
+pub const COIN_TYPE: u32 = super::coin_type(Regtest);
+
+/// The HRP for a Bech32-encoded regtest [`ExtendedSpendingKey`].
+///
+/// Defined in [ZIP 32].
+///
+/// [`ExtendedSpendingKey`]: crate::zip32::ExtendedSpendingKey
+/// [ZIP 32]: https://github.com/zcash/zips/blob/master/zip-0032.rst
+pub const HRP_SAPLING_EXTENDED_SPENDING_KEY: &str = super::hrp_sapling_extended_spending_key(Regtest);
+
+/// The HRP for a Bech32-encoded regtest [`ExtendedFullViewingKey`].
+///
+/// Defined in [ZIP 32].
+///
+/// [`ExtendedFullViewingKey`]: crate::zip32::ExtendedFullViewingKey
+/// [ZIP 32]: https://github.com/zcash/zips/blob/master/zip-0032.rst
+pub const HRP_SAPLING_EXTENDED_FULL_VIEWING_KEY: &str =
+    super::hrp_sapling_extended_full_viewing_key(Regtest);
+
+/// The HRP for a Bech32-encoded regtest [`PaymentAddress`].
+///
+/// Defined in section 5.6.4 of the [Zcash Protocol Specification].
+///
+/// [`PaymentAddress`]: crate::sapling::PaymentAddress
+/// [Zcash Protocol Specification]: https://github.com/zcash/zips/blob/master/protocol/protocol.pdf
+pub const HRP_SAPLING_PAYMENT_ADDRESS: &str = super::hrp_sapling_payment_address(Regtest);
+
+/// The prefix for a Base58Check-encoded regtest [`TransparentAddress::PublicKey`].
+///
+/// Matches the testnet prefix.
+///
+/// [`TransparentAddress::PublicKey`]: crate::legacy::TransparentAddress::PublicKey
+pub const B58_PUBKEY_ADDRESS_PREFIX: [u8; 1] = super::b58_pubkey_address_prefix(Regtest);
+
+/// The prefix for a Base58Check-encoded regtest [`TransparentAddress::Script`].
+///
+/// Matches the testnet prefix.
+///
+/// [`TransparentAddress::Script`]: crate::legacy::TransparentAddress::Script
+pub const B58_SCRIPT_ADDRESS_PREFIX: [u8; 1] = super::b58_script_address_prefix(Regtest);
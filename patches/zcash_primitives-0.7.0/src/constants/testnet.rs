@@ -1,9 +1,15 @@
 //! Constants for the Zcash test network.
+//!
+//! These are re-exported from the central [`constants`](super) table, keyed by
+//! [`NetworkType::Testnet`](crate::consensus::NetworkType::Testnet), so this
+//! module can't drift from the other networks' constants.
+
+use crate::consensus::NetworkType::Testnet;
 
 /// The testnet coin type for ZEC, as defined by [SLIP 44].
 ///
 /// [SLIP 44]: https://github.com/satoshilabs/slips/blob/master/slip-0044.md
-pub const COIN_TYPE: u32 = 1;
+pub const COIN_TYPE: u32 = super::coin_type(Testnet);
 
 /// The HRP for a Bech32-encoded testnet [`ExtendedSpendingKey`].
 ///
@@ -11,7 +17,7 @@ pub const COIN_TYPE: u32 = 1;
 ///
 /// [`ExtendedSpendingKey`]: crate::zip32::ExtendedSpendingKey
 /// [ZIP 32]: https://github.com/zcash/zips/blob/master/zip-0032.rst
-pub const HRP_SAPLING_EXTENDED_SPENDING_KEY: &str = "secret-extended-key-test";
+pub const HRP_SAPLING_EXTENDED_SPENDING_KEY: &str = super::hrp_sapling_extended_spending_key(Testnet);
 
 /// The HRP for a Bech32-encoded testnet [`ExtendedFullViewingKey`].
 ///
@@ -19,7 +25,8 @@ pub const HRP_SAPLING_EXTENDED_SPENDING_KEY: &str = "secret-extended-key-test";
 ///
 /// [`ExtendedFullViewingKey`]: crate::zip32::ExtendedFullViewingKey
 /// [ZIP 32]: https://github.com/zcash/zips/blob/master/zip-0032.rst
-pub const HRP_SAPLING_EXTENDED_FULL_VIEWING_KEY: &str = "zxviewtestsapling";
+pub const HRP_SAPLING_EXTENDED_FULL_VIEWING_KEY: &str =
+    super::hrp_sapling_extended_full_viewing_key(Testnet);
 
 /// The HRP for a Bech32-encoded testnet [`PaymentAddress`].
 ///
@@ -27,14 +34,14 @@ pub const HRP_SAPLING_EXTENDED_FULL_VIEWING_KEY: &str = "zxviewtestsapling";
 ///
 /// [`PaymentAddress`]: crate::sapling::PaymentAddress
 /// [Zcash Protocol Specification]: https://github.com/zcash/zips/blob/master/protocol/protocol.pdf
-pub const HRP_SAPLING_PAYMENT_ADDRESS: &str = "ztestsapling";
+pub const HRP_SAPLING_PAYMENT_ADDRESS: &str = super::hrp_sapling_payment_address(Testnet);
 
 /// The prefix for a Base58Check-encoded testnet [`TransparentAddress::PublicKey`].
 ///
 /// [`TransparentAddress::PublicKey`]: crate::legacy::TransparentAddress::PublicKey
-pub const B58_PUBKEY_ADDRESS_PREFIX: [u8; 1] = [0];
+pub const B58_PUBKEY_ADDRESS_PREFIX: [u8; 1] = super::b58_pubkey_address_prefix(Testnet);
 
 /// The prefix for a Base58Check-encoded testnet [`TransparentAddress::Script`].
 ///
 /// [`TransparentAddress::Script`]: crate::legacy::TransparentAddress::Script
-pub const B58_SCRIPT_ADDRESS_PREFIX: [u8; 1] = [5];
+pub const B58_SCRIPT_ADDRESS_PREFIX: [u8; 1] = super::b58_script_address_prefix(Testnet);
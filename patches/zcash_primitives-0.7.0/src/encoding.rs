@@ -0,0 +1,161 @@
+//! Bech32 and Base58Check encoding/decoding of addresses and keys.
+//!
+//! The HRPs and prefixes used here are taken from the [`consensus::Parameters`]
+//! implementation for the network being encoded/decoded for, so callers never
+//! need to hard-code a `constants::*` module path.
+
+use bech32::{self, FromBase32, ToBase32, Variant};
+
+use crate::{
+    consensus::Parameters,
+    legacy::TransparentAddress,
+    sapling::PaymentAddress,
+    zip32::{ExtendedFullViewingKey, ExtendedSpendingKey},
+};
+
+/// Errors that can occur while encoding or decoding addresses and keys.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EncodingError {
+    /// The Bech32 encoding was invalid.
+    InvalidBech32,
+    /// The Bech32 human-readable part did not match the one expected for this network.
+    WrongHrp {
+        expected: &'static str,
+        actual: String,
+    },
+    /// The decoded Bech32 data did not represent a valid value.
+    InvalidData,
+    /// The Base58Check encoding was invalid.
+    InvalidBase58Check,
+    /// The decoded Base58Check prefix did not match the one expected for this network.
+    WrongB58Prefix {
+        expected: [u8; 1],
+        actual: [u8; 1],
+    },
+}
+
+/// Encodes `spending_key` as a Bech32 string, using `params`'s
+/// [`Parameters::hrp_sapling_extended_spending_key`] HRP.
+pub fn encode_extended_spending_key<P: Parameters>(
+    params: &P,
+    spending_key: &ExtendedSpendingKey,
+) -> String {
+    bech32_encode(
+        params.hrp_sapling_extended_spending_key(),
+        &spending_key.to_bytes(),
+    )
+}
+
+/// Decodes a Bech32-encoded extended spending key, checking that its HRP matches
+/// `params`'s [`Parameters::hrp_sapling_extended_spending_key`].
+pub fn decode_extended_spending_key<P: Parameters>(
+    params: &P,
+    encoded: &str,
+) -> Result<ExtendedSpendingKey, EncodingError> {
+    let data = bech32_decode(params.hrp_sapling_extended_spending_key(), encoded)?;
+    ExtendedSpendingKey::from_bytes(&data).ok_or(EncodingError::InvalidData)
+}
+
+/// Encodes `key` as a Bech32 string, using `params`'s
+/// [`Parameters::hrp_sapling_extended_full_viewing_key`] HRP.
+pub fn encode_extended_full_viewing_key<P: Parameters>(
+    params: &P,
+    key: &ExtendedFullViewingKey,
+) -> String {
+    bech32_encode(
+        params.hrp_sapling_extended_full_viewing_key(),
+        &key.to_bytes(),
+    )
+}
+
+/// Decodes a Bech32-encoded extended full viewing key, checking that its HRP
+/// matches `params`'s [`Parameters::hrp_sapling_extended_full_viewing_key`].
+pub fn decode_extended_full_viewing_key<P: Parameters>(
+    params: &P,
+    encoded: &str,
+) -> Result<ExtendedFullViewingKey, EncodingError> {
+    let data = bech32_decode(params.hrp_sapling_extended_full_viewing_key(), encoded)?;
+    ExtendedFullViewingKey::from_bytes(&data).ok_or(EncodingError::InvalidData)
+}
+
+/// Encodes `address` as a Bech32 string, using `params`'s
+/// [`Parameters::hrp_sapling_payment_address`] HRP.
+pub fn encode_payment_address<P: Parameters>(params: &P, address: &PaymentAddress) -> String {
+    bech32_encode(params.hrp_sapling_payment_address(), &address.to_bytes())
+}
+
+/// Decodes a Bech32-encoded Sapling payment address, checking that its HRP
+/// matches `params`'s [`Parameters::hrp_sapling_payment_address`].
+pub fn decode_payment_address<P: Parameters>(
+    params: &P,
+    encoded: &str,
+) -> Result<PaymentAddress, EncodingError> {
+    let data = bech32_decode(params.hrp_sapling_payment_address(), encoded)?;
+    PaymentAddress::from_bytes(&data).ok_or(EncodingError::InvalidData)
+}
+
+/// Encodes `address` as a Base58Check string, using `params`'s pubkey/script
+/// address prefixes.
+pub fn encode_transparent_address<P: Parameters>(
+    params: &P,
+    address: &TransparentAddress,
+) -> String {
+    let (prefix, hash) = match address {
+        TransparentAddress::PublicKey(hash) => (params.b58_pubkey_address_prefix(), hash),
+        TransparentAddress::Script(hash) => (params.b58_script_address_prefix(), hash),
+    };
+
+    bs58::encode([&prefix[..], &hash[..]].concat())
+        .with_check()
+        .into_string()
+}
+
+/// Decodes a Base58Check-encoded transparent address, checking that its prefix
+/// matches one of `params`'s pubkey/script address prefixes.
+pub fn decode_transparent_address<P: Parameters>(
+    params: &P,
+    encoded: &str,
+) -> Result<TransparentAddress, EncodingError> {
+    let decoded = bs58::decode(encoded)
+        .with_check(None)
+        .into_vec()
+        .map_err(|_| EncodingError::InvalidBase58Check)?;
+
+    if decoded.len() != 21 {
+        return Err(EncodingError::InvalidData);
+    }
+
+    let mut prefix = [0u8; 1];
+    prefix.copy_from_slice(&decoded[0..1]);
+    let mut hash = [0u8; 20];
+    hash.copy_from_slice(&decoded[1..21]);
+
+    if prefix == params.b58_pubkey_address_prefix() {
+        Ok(TransparentAddress::PublicKey(hash))
+    } else if prefix == params.b58_script_address_prefix() {
+        Ok(TransparentAddress::Script(hash))
+    } else {
+        Err(EncodingError::WrongB58Prefix {
+            expected: params.b58_pubkey_address_prefix(),
+            actual: prefix,
+        })
+    }
+}
+
+fn bech32_encode(hrp: &str, data: &[u8]) -> String {
+    bech32::encode(hrp, data.to_base32(), Variant::Bech32)
+        .expect("hrp is ASCII and data is not too long")
+}
+
+fn bech32_decode(expected_hrp: &'static str, encoded: &str) -> Result<Vec<u8>, EncodingError> {
+    let (hrp, data, _variant) = bech32::decode(encoded).map_err(|_| EncodingError::InvalidBech32)?;
+
+    if hrp != expected_hrp {
+        return Err(EncodingError::WrongHrp {
+            expected: expected_hrp,
+            actual: hrp,
+        });
+    }
+
+    Vec::<u8>::from_base32(&data).map_err(|_| EncodingError::InvalidData)
+}
@@ -0,0 +1,87 @@
+//! Block heights.
+
+use std::convert::TryFrom;
+use std::num::TryFromIntError;
+use std::ops::Sub;
+
+/// A block height: the number of blocks between a block and the genesis
+/// block, which is height 0.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Height(pub u32);
+
+impl Height {
+    /// The minimum possible height: the genesis block.
+    pub const MIN: Height = Height(0);
+
+    /// The maximum possible height.
+    ///
+    /// Network upgrades don't use this as a sentinel: an upgrade that never
+    /// activates is left out of the activation tables, and
+    /// [`super::super::parameters::NetworkUpgrade::activation_range`] reports
+    /// the end of an open-ended upgrade as `None`.
+    pub const MAX: Height = Height(u32::MAX);
+
+    /// The maximum value of a transaction's expiry height.
+    ///
+    /// Consensus rule: `nExpiryHeight` MUST be less than or equal to
+    /// `499999999`.
+    pub const MAX_EXPIRY_HEIGHT: Height = Height(499_999_999);
+
+    /// Returns `self + rhs`, or `None` if the result would overflow
+    /// [`Height::MAX`].
+    pub fn checked_add(&self, rhs: u32) -> Option<Height> {
+        self.0.checked_add(rhs).map(Height)
+    }
+
+    /// Returns `self - rhs`, or `None` if the result would underflow
+    /// [`Height::MIN`].
+    pub fn checked_sub(&self, rhs: u32) -> Option<Height> {
+        self.0.checked_sub(rhs).map(Height)
+    }
+
+    /// Returns `self + rhs`, saturating at [`Height::MAX`] instead of
+    /// overflowing.
+    pub fn saturating_add(&self, rhs: u32) -> Height {
+        Height(self.0.saturating_add(rhs))
+    }
+
+    /// Returns `self - rhs`, saturating at [`Height::MIN`] instead of
+    /// underflowing.
+    pub fn saturating_sub(&self, rhs: u32) -> Height {
+        Height(self.0.saturating_sub(rhs))
+    }
+}
+
+/// Subtracts `rhs` from `self`, returning `None` if the result would
+/// underflow [`Height::MIN`] instead of panicking.
+///
+/// This is the signed counterpart to [`Height::checked_sub`], used where the
+/// caller already has a small signed offset (e.g. "one block before the
+/// tip") rather than an unsigned one.
+impl Sub<i32> for Height {
+    type Output = Option<Height>;
+
+    fn sub(self, rhs: i32) -> Option<Height> {
+        if rhs >= 0 {
+            self.checked_sub(rhs as u32)
+        } else {
+            self.checked_add(rhs.unsigned_abs())
+        }
+    }
+}
+
+impl TryFrom<i32> for Height {
+    type Error = TryFromIntError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        u32::try_from(value).map(Height)
+    }
+}
+
+impl TryFrom<i64> for Height {
+    type Error = TryFromIntError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        u32::try_from(value).map(Height)
+    }
+}
@@ -0,0 +1,14 @@
+//! Blocks and their components.
+//!
+//! # TODO
+//!
+//! This module only defines [`Height`] so far: `block::Hash`, `block::Block`,
+//! and `block::merkle` are referenced throughout the crate (e.g.
+//! `zebra-consensus/src/transaction/fast_sync.rs`,
+//! `zebra-consensus/src/transaction.rs`) but aren't reconstructed here —
+//! that's separate, larger surface than the checked-arithmetic/conversion
+//! API this module was added to land.
+
+mod height;
+
+pub use height::Height;
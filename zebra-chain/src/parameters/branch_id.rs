@@ -0,0 +1,132 @@
+//! Consensus branch ids: the value transaction sighashes commit to, so a
+//! transaction signed under one network upgrade's rules can't be replayed
+//! under another's.
+
+use std::{collections::BTreeMap, fmt};
+
+use hex::{FromHex, FromHexError, ToHex};
+
+use crate::block;
+
+use super::{Network, NetworkUpgrade};
+
+/// A Zcash consensus branch id.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ConsensusBranchId(u32);
+
+/// The consensus branch id each [`NetworkUpgrade`] that has one signs
+/// transactions with.
+///
+/// [`NetworkUpgrade::Genesis`] and [`NetworkUpgrade::BeforeOverwinter`] have
+/// no entry here: consensus branch ids were introduced by Overwinter, so
+/// transactions under either of those rules don't commit to one.
+///
+/// [`NetworkUpgrade::Overwinter`] has no entry here either: Komodo activates
+/// it at the same height as [`NetworkUpgrade::Sapling`]
+/// (`MAINNET_ACTIVATION_HEIGHTS`/`TESTNET_ACTIVATION_HEIGHTS`), so no block
+/// is ever signed under Overwinter's own branch id instead of Sapling's —
+/// [`NetworkUpgrade::current`] always resolves their shared height to
+/// Sapling.
+pub const CONSENSUS_BRANCH_IDS: &[(NetworkUpgrade, ConsensusBranchId)] = &[
+    (NetworkUpgrade::Sapling, ConsensusBranchId(0x76b8_09bb)),
+    (NetworkUpgrade::Blossom, ConsensusBranchId(0x2bb4_0e60)),
+    (NetworkUpgrade::Heartwood, ConsensusBranchId(0xf5b9_230b)),
+    (NetworkUpgrade::Canopy, ConsensusBranchId(0xe9ff_75a6)),
+    (NetworkUpgrade::Nu5, ConsensusBranchId(0xc2d6_d0b4)),
+    (NetworkUpgrade::ZFuture, ConsensusBranchId(0xffff_ffff)),
+];
+
+impl NetworkUpgrade {
+    /// Returns the consensus branch id `self` signs transactions with, or
+    /// `None` if `self` predates consensus branch ids.
+    pub fn branch_id(&self) -> Option<ConsensusBranchId> {
+        CONSENSUS_BRANCH_IDS
+            .iter()
+            .find(|(upgrade, _)| upgrade == self)
+            .map(|(_, branch_id)| *branch_id)
+    }
+
+    /// Returns the full list of consensus branch ids, keyed by the network
+    /// upgrade that signs with them.
+    pub fn branch_id_list() -> BTreeMap<NetworkUpgrade, ConsensusBranchId> {
+        CONSENSUS_BRANCH_IDS.iter().copied().collect()
+    }
+}
+
+/// Whether a node validates the chain itself, or runs as a Komodo NSPV
+/// ("superlite") client that trusts a remote full node for chain data.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ClientMode {
+    /// A full node that validates the chain itself.
+    FullNode,
+    /// Komodo's NSPV superlite client mode.
+    ///
+    /// A superlite client doesn't track the chain tip height closely enough
+    /// to derive a consensus branch id from it, so NSPV signs every
+    /// transaction with [`ConsensusBranchId::nspv`] instead.
+    NspvSuperlite,
+}
+
+impl ConsensusBranchId {
+    /// Komodo's fixed NSPV (superlite client) consensus branch id.
+    ///
+    /// This happens to share its numeric value with
+    /// [`NetworkUpgrade::Sapling`]'s entry in [`CONSENSUS_BRANCH_IDS`], but
+    /// the two are unrelated: this one is never looked up by height, only
+    /// ever returned directly for [`ClientMode::NspvSuperlite`].
+    const NSPV: ConsensusBranchId = ConsensusBranchId(0x76b8_09bb);
+
+    /// Returns [`Self::NSPV`], the branch id Komodo's NSPV superlite clients
+    /// sign transactions with.
+    pub const fn nspv() -> ConsensusBranchId {
+        Self::NSPV
+    }
+
+    /// Returns the consensus branch id active on `network` at `height`, or
+    /// `None` if no network upgrade active at that height has one.
+    pub fn current(network: Network, height: block::Height) -> Option<ConsensusBranchId> {
+        NetworkUpgrade::current(network, height).branch_id()
+    }
+
+    /// Returns the consensus branch id a node in `mode` signs transactions
+    /// with, on `network` at `height`.
+    ///
+    /// Under [`ClientMode::NspvSuperlite`] this is always
+    /// `Some(`[`Self::nspv`]`())`, regardless of `network`/`height`; under
+    /// [`ClientMode::FullNode`] it's the same as [`Self::current`].
+    pub fn current_for_client(
+        network: Network,
+        height: block::Height,
+        mode: ClientMode,
+    ) -> Option<ConsensusBranchId> {
+        match mode {
+            ClientMode::NspvSuperlite => Some(Self::nspv()),
+            ClientMode::FullNode => Self::current(network, height),
+        }
+    }
+}
+
+impl fmt::Display for ConsensusBranchId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:08x}", self.0)
+    }
+}
+
+impl FromHex for ConsensusBranchId {
+    type Error = FromHexError;
+
+    fn from_hex<T: AsRef<[u8]>>(hex: T) -> Result<Self, Self::Error> {
+        let bytes = <[u8; 4]>::from_hex(hex)?;
+        Ok(ConsensusBranchId(u32::from_be_bytes(bytes)))
+    }
+}
+
+impl ToHex for ConsensusBranchId {
+    fn encode_hex<T: FromIterator<char>>(&self) -> T {
+        self.0.to_be_bytes().encode_hex()
+    }
+
+    fn encode_hex_upper<T: FromIterator<char>>(&self) -> T {
+        self.0.to_be_bytes().encode_hex_upper()
+    }
+}
@@ -0,0 +1,14 @@
+//! Consensus parameters: the network upgrades and consensus branch ids that a
+//! [`Network`] activates, and the height (or branch id) each one takes over
+//! at.
+
+mod branch_id;
+mod network;
+mod network_upgrade;
+
+pub use branch_id::{ClientMode, ConsensusBranchId, CONSENSUS_BRANCH_IDS};
+pub use network::{Network, Parameters};
+pub use network_upgrade::{NetworkUpgrade, MAINNET_ACTIVATION_HEIGHTS, TESTNET_ACTIVATION_HEIGHTS};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,65 @@
+//! The Zcash/Komodo network a [`NetworkUpgrade`] or [`ConsensusBranchId`]
+//! applies to.
+
+use crate::block;
+
+use super::NetworkUpgrade;
+
+/// A Zcash/Komodo network.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Network {
+    /// The main network.
+    Mainnet,
+    /// The test network.
+    Testnet,
+}
+
+impl Network {
+    /// Returns `true` if `self` is a test network.
+    ///
+    /// [`NetworkUpgrade::ZFuture`] and other test-only consensus rules are
+    /// only ever active on a test network, never on
+    /// [`Mainnet`](Network::Mainnet).
+    pub fn is_a_test_network(&self) -> bool {
+        matches!(self, Network::Testnet)
+    }
+}
+
+/// Network-specific consensus parameters: the activation height of each
+/// [`NetworkUpgrade`].
+///
+/// Modeled on zcash_primitives' `consensus::Parameters`, this lets consensus
+/// rule code that only needs activation heights take `&dyn Parameters`
+/// instead of a concrete [`Network`]. [`NetworkUpgrade::activation_height`],
+/// which the consistency tests in [`super::tests`] call, looks heights up
+/// through this trait's [`Network`] implementation; `activation_bijective`
+/// still reads the `MAINNET_ACTIVATION_HEIGHTS`/`TESTNET_ACTIVATION_HEIGHTS`
+/// statics directly.
+///
+/// # TODO
+///
+/// No caller constructs a non-[`Network`] implementation yet: letting a
+/// Komodo assetchain supply its own table, loaded from config at runtime,
+/// needs `Network` (or a type alongside it) to carry that table, which is a
+/// larger, separate change to this type's public shape than this trait
+/// itself.
+pub trait Parameters {
+    /// Returns the height at which `nu` activates, or `None` if `nu` never
+    /// activates under these parameters.
+    fn activation_height(&self, nu: NetworkUpgrade) -> Option<block::Height>;
+
+    /// Returns `true` if `nu` is active at `height` under these parameters.
+    fn is_nu_active(&self, nu: NetworkUpgrade, height: block::Height) -> bool {
+        self.activation_height(nu)
+            .map_or(false, |activation_height| height >= activation_height)
+    }
+}
+
+impl Parameters for Network {
+    fn activation_height(&self, nu: NetworkUpgrade) -> Option<block::Height> {
+        NetworkUpgrade::activation_table(*self)
+            .iter()
+            .find(|(_, upgrade)| *upgrade == nu)
+            .map(|(height, _)| *height)
+    }
+}
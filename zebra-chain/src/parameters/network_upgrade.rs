@@ -0,0 +1,174 @@
+//! Zcash/Komodo network upgrades, and the height each one activates at.
+
+use std::{
+    collections::BTreeMap,
+    ops::Bound::{Excluded, Unbounded},
+};
+
+use crate::block;
+
+use super::{Network, Parameters};
+
+/// A Zcash network upgrade.
+///
+/// Network upgrades are always backwards-compatible with earlier upgrades: a
+/// block that's valid under an earlier upgrade's rules, and also valid under
+/// a later upgrade's rules, is valid once that later upgrade has activated.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(
+    any(test, feature = "proptest-impl"),
+    derive(proptest_derive::Arbitrary)
+)]
+pub enum NetworkUpgrade {
+    /// The genesis block.
+    Genesis,
+    /// The block network upgrade rules in place before Overwinter, also
+    /// known as Sprout.
+    BeforeOverwinter,
+    /// Overwinter.
+    Overwinter,
+    /// Sapling.
+    Sapling,
+    /// Blossom.
+    Blossom,
+    /// Heartwood.
+    Heartwood,
+    /// Canopy.
+    Canopy,
+    /// Nu5.
+    Nu5,
+    /// The `zcashd`/`komodod` future-test network upgrade, only ever active
+    /// on a [test network](Network::is_a_test_network).
+    ZFuture,
+}
+
+/// Mainnet network upgrade activation heights.
+///
+/// Komodo activates Overwinter and Sapling together, at the same height.
+/// Upgrades this build doesn't yet support (Blossom, Heartwood, Canopy, Nu5)
+/// are omitted entirely, rather than parked at a sentinel height: omitting
+/// them is what lets [`activation_height`](NetworkUpgrade::activation_height)
+/// return `None` for them, and lets [`is_activation_height`] and
+/// [`current`]/[`next`] skip them naturally instead of every parked upgrade
+/// colliding on the same sentinel key.
+///
+/// [`is_activation_height`]: NetworkUpgrade::is_activation_height
+/// [`current`]: NetworkUpgrade::current
+/// [`next`]: NetworkUpgrade::next
+pub const MAINNET_ACTIVATION_HEIGHTS: &[(block::Height, NetworkUpgrade)] = &[
+    (block::Height(0), NetworkUpgrade::Genesis),
+    (block::Height(1), NetworkUpgrade::BeforeOverwinter),
+    (block::Height(814_000), NetworkUpgrade::Overwinter),
+    (block::Height(814_000), NetworkUpgrade::Sapling),
+];
+
+/// Testnet network upgrade activation heights.
+///
+/// See [`MAINNET_ACTIVATION_HEIGHTS`] for Komodo's Overwinter/Sapling and
+/// disabled-upgrade conventions, both of which apply here too.
+pub const TESTNET_ACTIVATION_HEIGHTS: &[(block::Height, NetworkUpgrade)] = &[
+    (block::Height(0), NetworkUpgrade::Genesis),
+    (block::Height(1), NetworkUpgrade::BeforeOverwinter),
+    (block::Height(38_000), NetworkUpgrade::Overwinter),
+    (block::Height(38_000), NetworkUpgrade::Sapling),
+];
+
+impl NetworkUpgrade {
+    /// Returns `network`'s raw, un-deduplicated activation table: every
+    /// `(height, upgrade)` pair this build knows about, in declaration
+    /// order, including more than one entry at the same height.
+    ///
+    /// [`activation_list`](Self::activation_list) collapses same-height
+    /// entries into a `BTreeMap` (keeping only the last one), which is
+    /// enough for [`current`](Self::current)/[`next`](Self::next), but loses
+    /// the fact that more than one upgrade shares a height. Looking up a
+    /// single upgrade's own activation height, like
+    /// [`activation_height`](Self::activation_height) does, has to scan this
+    /// table directly instead, so that Komodo's Overwinter/Sapling
+    /// coincidence doesn't make one of the two report `None`.
+    pub(super) fn activation_table(network: Network) -> &'static [(block::Height, NetworkUpgrade)] {
+        match network {
+            Network::Mainnet => MAINNET_ACTIVATION_HEIGHTS,
+            Network::Testnet => TESTNET_ACTIVATION_HEIGHTS,
+        }
+    }
+
+    /// Returns the height at which `self` activates on `network`.
+    ///
+    /// Dispatches through [`Network`]'s [`Parameters`] implementation, so
+    /// that a future parameter set that isn't a bare [`Network`] can answer
+    /// this the same way.
+    pub fn activation_height(&self, network: Network) -> Option<block::Height> {
+        network.activation_height(*self)
+    }
+
+    /// Returns the full list of activation heights for `network`, keyed by
+    /// height.
+    ///
+    /// Heights shared by more than one upgrade (Komodo's Overwinter/Sapling)
+    /// collapse to whichever upgrade is declared last in
+    /// [`MAINNET_ACTIVATION_HEIGHTS`]/[`TESTNET_ACTIVATION_HEIGHTS`] for that
+    /// height.
+    pub fn activation_list(network: Network) -> BTreeMap<block::Height, NetworkUpgrade> {
+        Self::activation_table(network).iter().copied().collect()
+    }
+
+    /// Returns `true` if `height` is the activation height of some upgrade
+    /// on `network`.
+    pub fn is_activation_height(network: Network, height: block::Height) -> bool {
+        Self::activation_list(network).contains_key(&height)
+    }
+
+    /// Returns the network upgrade that's active on `network` at `height`.
+    pub fn current(network: Network, height: block::Height) -> NetworkUpgrade {
+        Self::activation_list(network)
+            .range(..=height)
+            .map(|(_, upgrade)| *upgrade)
+            .next_back()
+            .unwrap_or(NetworkUpgrade::Genesis)
+    }
+
+    /// Returns the next network upgrade to activate on `network` after
+    /// `height`, if any.
+    pub fn next(network: Network, height: block::Height) -> Option<NetworkUpgrade> {
+        Self::activation_list(network)
+            .range((Excluded(height), Unbounded))
+            .map(|(_, upgrade)| *upgrade)
+            .next()
+    }
+
+    /// Returns the half-open height range `[start, end)` during which `nu` is
+    /// the active upgrade on `network`, or `None` if `nu` never activates.
+    ///
+    /// `end` is the activation height of the upgrade that supersedes `nu`, or
+    /// `None` if `nu` is still the tip upgrade. Consensus rule code that only
+    /// needs to know whether `nu` is active at a height should call
+    /// [`is_nu_active`] instead of reconstructing this range by hand.
+    ///
+    /// [`is_nu_active`]: NetworkUpgrade::is_nu_active
+    pub fn activation_range(
+        network: Network,
+        nu: NetworkUpgrade,
+    ) -> Option<(block::Height, Option<block::Height>)> {
+        let start = nu.activation_height(network)?;
+        let end = Self::next(network, start).and_then(|next_nu| next_nu.activation_height(network));
+
+        Some((start, end))
+    }
+
+    /// Returns `true` if `nu` is the active upgrade on `network` at `height`.
+    ///
+    /// Unlike zcash_primitives' `Parameters::is_nu_active` (and this crate's
+    /// [`Parameters::is_nu_active`]), which stay `true` at every height from
+    /// `nu`'s activation onward, this returns `false` once the upgrade after
+    /// `nu` activates. It's a bare function taking `network` rather than a
+    /// method on a `Parameters` impl, matching
+    /// [`current`](Self::current)/[`next`](Self::next) above.
+    pub fn is_nu_active(network: Network, nu: NetworkUpgrade, height: block::Height) -> bool {
+        match Self::activation_range(network, nu) {
+            Some((start, None)) => height >= start,
+            Some((start, Some(end))) => height >= start && height < end,
+            None => false,
+        }
+    }
+}
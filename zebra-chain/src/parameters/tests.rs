@@ -10,7 +10,16 @@ use Network::*;
 use NetworkUpgrade::*;
 
 /// Check that the activation heights and network upgrades are unique.
-#[ignore]  // fix for Komodo
+///
+/// Still doesn't hold for Komodo: omitting disabled upgrades (instead of
+/// parking them at `block::Height::MAX`) fixed the sentinel collision this
+/// test used to fail on, but Komodo activates Overwinter and Sapling at the
+/// same height, which is a second, permanent source of non-uniqueness that
+/// `komodo_activation_extremes` (below) asserts is intentional. Making both
+/// invariants hold at once would mean either giving Overwinter its own
+/// height or dropping it from the activation table, either of which is a
+/// bigger change than this fix.
+#[ignore] // fix for Komodo: Overwinter and Sapling share an activation height
 #[test]
 fn activation_bijective() {
     zebra_test::init();
@@ -106,27 +115,30 @@ fn komodo_activation_extremes(network: Network) {
         Some(&Genesis)
     );
 
-    /* disabled for Komodo where unused upgrades are set at block::Height::MAX
+    // Now that disabled upgrades are omitted instead of parked at
+    // `block::Height::MAX`, `MAX` is never an activation height.
     assert!(!NetworkUpgrade::is_activation_height(
         network,
         block::Height::MAX
-    )); */
+    ));
 
     assert_ne!(
         NetworkUpgrade::current(network, block::Height::MAX),
         Genesis
     );
     assert_eq!(NetworkUpgrade::next(network, block::Height::MAX), None);
+
+    // `Height::MAX` is a well-defined edge case, not a panic: checked
+    // arithmetic past it returns `None` instead of wrapping or overflowing.
+    assert_eq!(block::Height::MAX.checked_add(1), None);
 }
 
-#[ignore]  // fix for Komodo
 #[test]
 fn activation_consistent_mainnet() {
     zebra_test::init();
     activation_consistent(Mainnet)
 }
 
-#[ignore]  // fix for Komodo
 #[test]
 fn activation_consistent_testnet() {
     zebra_test::init();
@@ -135,6 +147,10 @@ fn activation_consistent_testnet() {
 
 /// Check that the `activation_height`, `is_activation_height`,
 /// `current`, and `next` functions are consistent for `network`.
+///
+/// Uses `block::Height::checked_add` rather than the panicking `+`, so a
+/// height adjacent to `Height::MAX` is well-defined instead of unwrapping a
+/// `None`.
 fn activation_consistent(network: Network) {
     let activation_list = NetworkUpgrade::activation_list(network);
     let network_upgrades: HashSet<&NetworkUpgrade> = activation_list.values().collect();
@@ -148,9 +164,14 @@ fn activation_consistent(network: Network) {
         if height > block::Height(0) {
             // Genesis is immediately followed by BeforeOverwinter,
             // but the other network upgrades have multiple blocks between them
+            //
+            // `height` is an activation height, so it's always strictly less
+            // than `Height::MAX`: `checked_add` can't overflow here, but we
+            // still reach for it over the panicking `+` so this keeps
+            // working if a future activation height ever lands at the edge.
             assert!(!NetworkUpgrade::is_activation_height(
                 network,
-                (height + 1).unwrap()
+                height.checked_add(1).expect("height is not Height::MAX")
             ));
         }
 
@@ -158,7 +179,10 @@ fn activation_consistent(network: Network) {
         // Network upgrades don't repeat
         assert_ne!(NetworkUpgrade::next(network, height), Some(network_upgrade));
         assert_ne!(
-            NetworkUpgrade::next(network, block::Height(height.0 + 1)),
+            NetworkUpgrade::next(
+                network,
+                height.checked_add(1).expect("height is not Height::MAX")
+            ),
             Some(network_upgrade)
         );
         assert_ne!(
@@ -168,6 +192,59 @@ fn activation_consistent(network: Network) {
     }
 }
 
+#[test]
+fn activation_range_tiles_mainnet() {
+    zebra_test::init();
+    activation_range_tiles(Mainnet)
+}
+
+#[test]
+fn activation_range_tiles_testnet() {
+    zebra_test::init();
+    activation_range_tiles(Testnet)
+}
+
+/// Check that `activation_range`'s windows tile the height space for
+/// `network`: every active upgrade's range starts exactly where its
+/// predecessor's ends, with no gap and no overlap, and `is_nu_active` agrees
+/// with the range at its own boundaries.
+fn activation_range_tiles(network: Network) {
+    let activation_list = NetworkUpgrade::activation_list(network);
+    let heights: Vec<block::Height> = activation_list.keys().copied().collect();
+
+    for (index, &height) in heights.iter().enumerate() {
+        let network_upgrade = activation_list[&height];
+        let (start, end) = NetworkUpgrade::activation_range(network, network_upgrade)
+            .expect("an upgrade in the activation list has a range");
+        assert_eq!(start, height);
+
+        match heights.get(index + 1) {
+            // No gap: this upgrade's range ends exactly where the next one's
+            // range starts.
+            Some(&next_height) => assert_eq!(end, Some(next_height)),
+            // The last upgrade in the list is the tip upgrade: its range
+            // never ends.
+            None => assert_eq!(end, None),
+        }
+
+        assert!(NetworkUpgrade::is_nu_active(network, network_upgrade, start));
+        if let Some(end) = end {
+            assert!(!NetworkUpgrade::is_nu_active(
+                network,
+                network_upgrade,
+                end
+            ));
+        }
+    }
+
+    assert!(NetworkUpgrade::activation_range(network, ZFuture).is_none());
+    assert!(!NetworkUpgrade::is_nu_active(
+        network,
+        ZFuture,
+        block::Height::MAX
+    ));
+}
+
 /// Check that the network upgrades and branch ids are unique.
 #[test]
 fn branch_id_bijective() {
@@ -217,16 +294,47 @@ fn branch_id_extremes(network: Network) {
         ConsensusBranchId::current(network, block::Height::MAX),
         None
     );
+
+    // An NSPV superlite client always signs with the fixed NSPV branch id,
+    // no matter the height or which network upgrade is current there.
+    assert_eq!(
+        ConsensusBranchId::current_for_client(network, block::Height(0), ClientMode::NspvSuperlite),
+        Some(ConsensusBranchId::nspv())
+    );
+    assert_eq!(
+        ConsensusBranchId::current_for_client(
+            network,
+            block::Height::MAX,
+            ClientMode::NspvSuperlite
+        ),
+        Some(ConsensusBranchId::nspv())
+    );
+    assert_eq!(
+        ConsensusBranchId::current_for_client(network, block::Height::MAX, ClientMode::FullNode),
+        ConsensusBranchId::current(network, block::Height::MAX)
+    );
+}
+
+/// Check that the NSPV branch id round-trips through hex the same way every
+/// other branch id does in [`branch_id_hex_roundtrip`].
+#[test]
+fn nspv_branch_id_hex_roundtrip() {
+    zebra_test::init();
+
+    let branch = ConsensusBranchId::nspv();
+    let hex_branch: String = branch.encode_hex();
+    let new_branch =
+        ConsensusBranchId::from_hex(hex_branch.clone()).expect("hex branch_id should parse");
+    assert_eq!(branch, new_branch);
+    assert_eq!(hex_branch, new_branch.to_string());
 }
 
-#[ignore] // TODO: fix for komodo where Overwinter Blossom etc do not have activation height
 #[test]
 fn branch_id_consistent_mainnet() {
     zebra_test::init();
     branch_id_consistent(Mainnet)
 }
 
-#[ignore] // TODO: fix for komodo where Overwinter Blossom etc do not have activation height
 #[test]
 fn branch_id_consistent_testnet() {
     zebra_test::init();
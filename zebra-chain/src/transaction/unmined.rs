@@ -0,0 +1,65 @@
+//! Types for transactions that have not yet been mined into a block.
+//!
+//! This file only defines [`VerifiedUnminedTx`]; [`UnminedTx`] and
+//! `UnminedTxId` live in the parent `transaction` module and are unchanged
+//! here.
+
+use crate::{
+    amount::{Amount, NonNegative},
+    serialization::ZcashSerialize,
+};
+
+use super::UnminedTx;
+
+/// A verified mempool transaction, and the extra fields that verification
+/// computed along the way, so that later mempool and mining consumers don't
+/// have to recompute them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifiedUnminedTx {
+    /// The unmined transaction.
+    pub transaction: UnminedTx,
+
+    /// The transaction's miner fee, as validated during verification.
+    pub miner_fee: Amount<NonNegative>,
+
+    /// The Komodo interest claimed by this transaction, as validated during
+    /// verification.
+    pub interest: Amount<NonNegative>,
+
+    /// The number of legacy transparent signature operations in this
+    /// transaction's inputs and outputs, as counted during verification.
+    ///
+    /// Used by the block template assembler to enforce a block sigop budget
+    /// without re-counting scripts for every candidate transaction.
+    pub legacy_sigop_count: u64,
+
+    /// The serialized size of [`Self::transaction`], in bytes, as measured
+    /// during verification.
+    ///
+    /// Used by the block template assembler for fee-per-byte ordering and to
+    /// enforce a block size budget without re-serializing the transaction.
+    pub serialized_size: usize,
+}
+
+impl VerifiedUnminedTx {
+    /// Creates a new verified unmined transaction from its parts.
+    pub fn new(
+        transaction: UnminedTx,
+        miner_fee: Amount<NonNegative>,
+        interest: Amount<NonNegative>,
+        legacy_sigop_count: u64,
+    ) -> Self {
+        let serialized_size = transaction
+            .transaction
+            .zcash_serialized_size()
+            .expect("a verified transaction must have a valid serialized size");
+
+        Self {
+            transaction,
+            miner_fee,
+            interest,
+            legacy_sigop_count,
+            serialized_size,
+        }
+    }
+}
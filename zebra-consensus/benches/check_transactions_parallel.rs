@@ -0,0 +1,50 @@
+//! Benchmarks the scheduling overhead of
+//! [`zebra_consensus::transaction::check::check_transactions_parallel`]'s "first failure by
+//! index" selection, at a range of batch sizes.
+//!
+//! # TODO
+//!
+//! This doesn't benchmark the function against real [`Transaction`](zebra_chain::transaction::Transaction)s:
+//! this snapshot doesn't have `zebra_chain::transaction`'s defining module, so there's no
+//! way here to construct a batch of real transactions crafted to exercise
+//! `coinbase_outputs_are_decryptable`'s trial decryption, which the function's own doc
+//! comment names as what dominates the real cost of a batch. What's benchmarked instead is
+//! the `rayon` `par_iter().enumerate().find_map_first(..)` scheduling pattern itself, over
+//! synthetic per-item results, which is the part of this function this snapshot can
+//! actually exercise.
+//!
+//! There's also no `Cargo.toml` anywhere in this snapshot to add a `[[bench]]` entry or a
+//! `criterion` dev-dependency to, so this file isn't wired into a harness; it's written the
+//! way it would be if that manifest existed.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rayon::prelude::*;
+
+fn first_failure(fails: &[bool]) -> Option<usize> {
+    fails
+        .par_iter()
+        .enumerate()
+        .find_map_first(|(index, &fails)| fails.then_some(index))
+}
+
+fn bench_first_failure(c: &mut Criterion) {
+    let mut group = c.benchmark_group("check_transactions_parallel_selection");
+
+    for batch_size in [8, 64, 512, 4096] {
+        // The worst case for `find_map_first`: only the last transaction fails, so the
+        // thread pool can't skip checking any of the earlier ones.
+        let mut fails = vec![false; batch_size];
+        fails[batch_size - 1] = true;
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(batch_size),
+            &fails,
+            |b, fails| b.iter(|| first_failure(fails)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_first_failure);
+criterion_main!(benches);
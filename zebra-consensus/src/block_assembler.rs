@@ -0,0 +1,225 @@
+//! Assembling a block template from already-verified mempool transactions.
+//!
+//! This module turns a stream of [`VerifiedUnminedTx`] into the ordered subset
+//! that fits into a candidate block, so that a miner doesn't have to reinvent
+//! fee-rate ordering and size/sigop budgeting every time it wants a template.
+
+use std::collections::{HashMap, HashSet};
+
+use zebra_chain::{
+    amount::{Amount, NonNegative},
+    transparent,
+};
+
+use crate::transaction::VerifiedUnminedTx;
+
+/// The maximum serialized size of a block, in bytes.
+///
+/// <https://zips.z.cash/protocol/protocol.pdf#blockheader>
+pub const MAX_BLOCK_SIZE: usize = 2_000_000;
+
+/// The maximum number of legacy transparent signature operations permitted in a block.
+pub const MAX_BLOCK_SIGOPS: u64 = 20_000;
+
+/// The order in which candidate transactions are offered to the block template.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OrderingStrategy {
+    /// Order by the package's absolute miner fee, highest first.
+    AbsoluteFee,
+    /// Order by fee-per-byte (`package_fee / package_size`), highest first.
+    FeePerByte,
+}
+
+/// The result of assembling a block template: the selected transactions, in
+/// the order they should be included, plus their total fee.
+#[derive(Clone, Debug, Default)]
+pub struct BlockTemplate {
+    /// The selected transactions, in inclusion order.
+    pub transactions: Vec<VerifiedUnminedTx>,
+    /// The sum of `miner_fee` over `transactions`.
+    pub total_fee: Amount<NonNegative>,
+}
+
+/// Selects the subset of `candidates` that fits into a block, packing by
+/// `strategy` under the [`MAX_BLOCK_SIZE`] and [`MAX_BLOCK_SIGOPS`] budgets.
+///
+/// A candidate whose transparent inputs spend outputs of another candidate in
+/// this same set is part of that candidate's in-mempool ancestor package: a
+/// package is only ever selected as a whole, all-or-nothing, and is ranked by
+/// its combined (package) fee-rate rather than any single transaction's own
+/// fee-rate. This lets a low-fee parent that's paid for by a high-fee child
+/// (CPFP) be selected as early as the child's own fee-rate would allow.
+pub fn select_block_template(
+    candidates: Vec<VerifiedUnminedTx>,
+    strategy: OrderingStrategy,
+) -> BlockTemplate {
+    // Transparent outpoints created by a candidate, used to find the
+    // in-mempool ancestors of another candidate.
+    let produced_by: HashMap<transparent::OutPoint, usize> = candidates
+        .iter()
+        .enumerate()
+        .flat_map(|(index, candidate)| {
+            let txid = candidate.transaction.transaction.hash();
+            (0..candidate.transaction.transaction.outputs().len()).map(move |output_index| {
+                (
+                    transparent::OutPoint {
+                        hash: txid,
+                        index: output_index as u32,
+                    },
+                    index,
+                )
+            })
+        })
+        .collect();
+
+    let ancestors: Vec<HashSet<usize>> = (0..candidates.len())
+        .map(|index| ancestor_indices(index, &candidates, &produced_by))
+        .collect();
+
+    let order = rank_by_package_fee_rate(&candidates, &ancestors, strategy);
+
+    let mut selected = HashSet::new();
+    let mut template = BlockTemplate::default();
+    let mut block_size = 0usize;
+    let mut block_sigops = 0u64;
+
+    // Keep looping over the package-ranked order until a full pass selects
+    // nothing new: selecting one package can shrink the remaining ancestor
+    // set (and so the cost) of a package that was already skipped earlier in
+    // this pass for not fitting.
+    loop {
+        let mut selected_this_pass = false;
+
+        for &index in &order {
+            if selected.contains(&index) {
+                continue;
+            }
+
+            // Select the whole package at once: this candidate, plus every
+            // ancestor that hasn't already been selected (e.g. by an earlier
+            // package in this same pass). An already-selected ancestor isn't
+            // double-counted towards the block's size or sigop budget.
+            let package: Vec<usize> = ancestors[index]
+                .iter()
+                .copied()
+                .chain(std::iter::once(index))
+                .filter(|member| !selected.contains(member))
+                .collect();
+
+            let package_size: usize = package.iter().map(|&i| candidates[i].serialized_size).sum();
+            let package_sigops: u64 = package.iter().map(|&i| candidates[i].legacy_sigop_count).sum();
+
+            if block_size + package_size > MAX_BLOCK_SIZE
+                || block_sigops + package_sigops > MAX_BLOCK_SIGOPS
+            {
+                continue;
+            }
+
+            block_size += package_size;
+            block_sigops += package_sigops;
+
+            // Ancestors must be added before their descendant, regardless of
+            // which candidate's package pulled them in.
+            let mut package = package;
+            package.sort_by_key(|&i| ancestors[i].len());
+
+            for member in package {
+                let candidate = &candidates[member];
+                template.total_fee = (template.total_fee + candidate.miner_fee)
+                    .expect("sum of selected miner fees fits in an Amount");
+                template.transactions.push(candidate.clone());
+                selected.insert(member);
+            }
+
+            selected_this_pass = true;
+        }
+
+        if !selected_this_pass {
+            break;
+        }
+    }
+
+    template
+}
+
+/// Returns the transitive set of candidate indices that produced an outpoint
+/// spent by `index` (directly or through another in-mempool ancestor),
+/// excluding `index` itself.
+fn ancestor_indices(
+    index: usize,
+    candidates: &[VerifiedUnminedTx],
+    produced_by: &HashMap<transparent::OutPoint, usize>,
+) -> HashSet<usize> {
+    let mut ancestors = HashSet::new();
+    let mut pending = vec![index];
+
+    while let Some(member) = pending.pop() {
+        for outpoint in spent_outpoints(&candidates[member]) {
+            if let Some(&parent) = produced_by.get(&outpoint) {
+                if ancestors.insert(parent) {
+                    pending.push(parent);
+                }
+            }
+        }
+    }
+
+    ancestors
+}
+
+/// Returns the transparent outpoints spent by `candidate`'s inputs.
+fn spent_outpoints(candidate: &VerifiedUnminedTx) -> impl Iterator<Item = transparent::OutPoint> + '_ {
+    candidate
+        .transaction
+        .transaction
+        .inputs()
+        .iter()
+        .filter_map(|input| match input {
+            transparent::Input::PrevOut { outpoint, .. } => Some(*outpoint),
+            transparent::Input::Coinbase { .. } => None,
+        })
+}
+
+/// Returns candidate indices ordered by descending package fee-rate under
+/// `strategy`, where a candidate's package is itself plus `ancestors`.
+fn rank_by_package_fee_rate(
+    candidates: &[VerifiedUnminedTx],
+    ancestors: &[HashSet<usize>],
+    strategy: OrderingStrategy,
+) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by_key(|&index| {
+        std::cmp::Reverse(package_ordering_key(index, candidates, ancestors, strategy))
+    });
+    order
+}
+
+/// Returns the ordering key for candidate `index`'s package under `strategy`,
+/// as a value where higher means "select earlier".
+fn package_ordering_key(
+    index: usize,
+    candidates: &[VerifiedUnminedTx],
+    ancestors: &[HashSet<usize>],
+    strategy: OrderingStrategy,
+) -> u64 {
+    let package_fee: i64 = ancestors[index]
+        .iter()
+        .copied()
+        .chain(std::iter::once(index))
+        .map(|member| i64::from(candidates[member].miner_fee))
+        .sum();
+    let package_fee = package_fee.max(0) as u64;
+
+    match strategy {
+        OrderingStrategy::AbsoluteFee => package_fee,
+        OrderingStrategy::FeePerByte => {
+            let package_size: usize = ancestors[index]
+                .iter()
+                .copied()
+                .chain(std::iter::once(index))
+                .map(|member| candidates[member].serialized_size)
+                .sum();
+
+            package_fee / package_size.max(1) as u64
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Trial-decryption of a transaction's shielded outputs.
+//!
+//! Unlike [`Verifier`](super::Verifier)'s consensus checks, decrypting a note
+//! doesn't need a valid proof or binding signature: it only needs the same
+//! Sapling/Orchard bundle traversal [`ShieldedBundle`](super::ShieldedBundle)
+//! already walks, tried against the viewing keys a wallet or indexer already
+//! holds. This gives those callers a single service entry point for scanning
+//! confirmed transactions, without running a separate light-client stack.
+
+use zebra_chain::{memo::MemoBytes, orchard, sapling, transaction::Transaction};
+
+/// A caller-assigned identifier for the account a viewing key belongs to.
+///
+/// `Verifier` doesn't assign these itself: it echoes back whichever
+/// `account` a matching key was registered under, on every output that key
+/// decrypts.
+pub type AccountId = u32;
+
+/// An incoming viewing key used to trial-decrypt shielded outputs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IncomingViewingKey {
+    /// A Sapling incoming viewing key.
+    Sapling(sapling::keys::IncomingViewingKey),
+    /// An Orchard incoming viewing key.
+    Orchard(orchard::keys::IncomingViewingKey),
+}
+
+/// An outgoing viewing key used to recover the outputs of a transaction its
+/// holder sent, without needing the corresponding incoming viewing key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OutgoingViewingKey {
+    /// A Sapling outgoing viewing key.
+    Sapling(sapling::keys::OutgoingViewingKey),
+    /// An Orchard outgoing viewing key.
+    Orchard(orchard::keys::OutgoingViewingKey),
+}
+
+/// A note recovered by trial-decrypting a shielded output.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DecryptedNote {
+    /// A decrypted Sapling note.
+    Sapling(sapling::Note),
+    /// A decrypted Orchard note.
+    Orchard(orchard::Note),
+}
+
+/// The recipient address of a decrypted note.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Recipient {
+    /// A Sapling payment address.
+    Sapling(sapling::PaymentAddress),
+    /// An Orchard payment address.
+    Orchard(orchard::Address),
+}
+
+/// A shielded output successfully trial-decrypted against a registered
+/// viewing key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecryptedOutput {
+    /// The index of this output within its pool's bundle (`shielded_outputs`
+    /// for Sapling, `actions` for Orchard).
+    pub index: usize,
+    /// The decrypted note.
+    pub note: DecryptedNote,
+    /// The note's recipient address.
+    pub recipient: Recipient,
+    /// The note's attached memo.
+    pub memo: MemoBytes,
+    /// The account whose key decrypted this output.
+    pub account: AccountId,
+    /// `true` if this output was recovered with an outgoing viewing key
+    /// (the account sent it), `false` if with an incoming viewing key (the
+    /// account received it).
+    pub outgoing: bool,
+}
+
+/// Trial-decrypts every Sapling and Orchard output of `transaction` against
+/// `ivks` and `ovks`, returning every output that matched one of them.
+///
+/// This does not verify `transaction`: a transaction that hasn't passed (or
+/// has failed) [`Verifier`](super::Verifier)'s consensus checks can still be
+/// decrypted, which is what lets a wallet scan mempool or reorged-out
+/// transactions the same way it scans confirmed ones.
+pub fn decrypt_outputs(
+    transaction: &Transaction,
+    ivks: &[(AccountId, IncomingViewingKey)],
+    ovks: &[(AccountId, OutgoingViewingKey)],
+) -> Vec<DecryptedOutput> {
+    let mut decrypted = Vec::new();
+
+    match transaction {
+        Transaction::V4 {
+            sapling_shielded_data,
+            ..
+        } => {
+            if let Some(bundle) = sapling_shielded_data {
+                decrypt_sapling_outputs(bundle, ivks, ovks, &mut decrypted);
+            }
+        }
+        Transaction::V5 {
+            sapling_shielded_data,
+            orchard_shielded_data,
+            ..
+        } => {
+            if let Some(bundle) = sapling_shielded_data {
+                decrypt_sapling_outputs(bundle, ivks, ovks, &mut decrypted);
+            }
+            if let Some(bundle) = orchard_shielded_data {
+                decrypt_orchard_outputs(bundle, ivks, ovks, &mut decrypted);
+            }
+        }
+        Transaction::V1 { .. } | Transaction::V2 { .. } | Transaction::V3 { .. } => {}
+    }
+
+    decrypted
+}
+
+/// Trial-decrypts every output of a Sapling bundle, appending every match to
+/// `decrypted`.
+fn decrypt_sapling_outputs<A>(
+    bundle: &sapling::ShieldedData<A>,
+    ivks: &[(AccountId, IncomingViewingKey)],
+    ovks: &[(AccountId, OutgoingViewingKey)],
+    decrypted: &mut Vec<DecryptedOutput>,
+) where
+    A: sapling::AnchorVariant,
+{
+    for (index, output) in bundle.outputs().enumerate() {
+        for (account, ivk) in ivks {
+            let IncomingViewingKey::Sapling(ivk) = ivk else {
+                continue;
+            };
+
+            if let Some((note, recipient, memo)) = sapling::try_note_decryption(ivk, output) {
+                decrypted.push(DecryptedOutput {
+                    index,
+                    note: DecryptedNote::Sapling(note),
+                    recipient: Recipient::Sapling(recipient),
+                    memo,
+                    account: *account,
+                    outgoing: false,
+                });
+            }
+        }
+
+        for (account, ovk) in ovks {
+            let OutgoingViewingKey::Sapling(ovk) = ovk else {
+                continue;
+            };
+
+            if let Some((note, recipient, memo)) = sapling::try_output_recovery(ovk, output) {
+                decrypted.push(DecryptedOutput {
+                    index,
+                    note: DecryptedNote::Sapling(note),
+                    recipient: Recipient::Sapling(recipient),
+                    memo,
+                    account: *account,
+                    outgoing: true,
+                });
+            }
+        }
+    }
+}
+
+/// Trial-decrypts every action of an Orchard bundle, appending every match
+/// to `decrypted`.
+fn decrypt_orchard_outputs(
+    bundle: &orchard::ShieldedData,
+    ivks: &[(AccountId, IncomingViewingKey)],
+    ovks: &[(AccountId, OutgoingViewingKey)],
+    decrypted: &mut Vec<DecryptedOutput>,
+) {
+    for (index, authorized_action) in bundle.actions.iter().enumerate() {
+        let (action, _spend_auth_sig) = authorized_action.clone().into_parts();
+
+        for (account, ivk) in ivks {
+            let IncomingViewingKey::Orchard(ivk) = ivk else {
+                continue;
+            };
+
+            if let Some((note, recipient, memo)) = orchard::try_note_decryption(ivk, &action) {
+                decrypted.push(DecryptedOutput {
+                    index,
+                    note: DecryptedNote::Orchard(note),
+                    recipient: Recipient::Orchard(recipient),
+                    memo,
+                    account: *account,
+                    outgoing: false,
+                });
+            }
+        }
+
+        for (account, ovk) in ovks {
+            let OutgoingViewingKey::Orchard(ovk) = ovk else {
+                continue;
+            };
+
+            if let Some((note, recipient, memo)) = orchard::try_output_recovery(ovk, &action) {
+                decrypted.push(DecryptedOutput {
+                    index,
+                    note: DecryptedNote::Orchard(note),
+                    recipient: Recipient::Orchard(recipient),
+                    memo,
+                    account: *account,
+                    outgoing: true,
+                });
+            }
+        }
+    }
+}
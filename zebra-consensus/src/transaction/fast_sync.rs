@@ -0,0 +1,168 @@
+//! Fast-sync: skip expensive per-transaction checks below a trusted,
+//! batch-verified historical horizon.
+//!
+//! Each batch of [`FAST_SYNC_BATCH_SIZE`] consecutive blocks has a
+//! compile-time embedded "summary hash": the double-SHA256 of the
+//! concatenation of every block hash in the batch, in height order. As each
+//! batch of blocks arrives during sync, the batch's summary hash is
+//! recomputed from the incoming block hashes and compared against the
+//! embedded value with [`batch_is_trusted`]. A match means the batch is
+//! byte-for-byte identical to the canonical historical chain, so the
+//! expensive per-transaction checks in [`crate::transaction::check`] can be
+//! skipped for every transaction in it, by checking them with a
+//! [`TransactionContext::fast_sync`] context. A mismatch falls back to full
+//! verification for the whole batch.
+//!
+//! [`fast_sync_horizon`] is the first block height not covered by the
+//! embedded list; from it onward, every block is verified fully, exactly as
+//! it is today.
+//!
+//! # TODO
+//!
+//! [`MAINNET_BATCH_SUMMARIES`] and [`TESTNET_BATCH_SUMMARIES`] are both
+//! empty: populating them needs the real block hash history of each
+//! network, which isn't available to generate from this snapshot. Until
+//! they're populated, [`fast_sync_horizon`] returns `block::Height(0)` for
+//! both networks, so no block is ever considered trusted and every check
+//! keeps running in full.
+//!
+//! Recomputing and checking a batch's summary hash as blocks arrive is sync
+//! pipeline work, and happens outside this module; the sync pipeline isn't
+//! part of this snapshot.
+
+use sha2::{Digest, Sha256};
+
+use zebra_chain::{block, parameters::Network};
+
+/// The number of consecutive blocks summarized by one embedded hash.
+pub const FAST_SYNC_BATCH_SIZE: u32 = 25_000;
+
+/// One batch's summary hash: the double-SHA256 of its block hashes,
+/// concatenated in height order.
+pub type BatchSummary = [u8; 32];
+
+/// Mainnet's embedded batch summaries, one per [`FAST_SYNC_BATCH_SIZE`]-block
+/// batch starting at height 0, in order.
+///
+/// Empty until real mainnet block hash history is available; see the module
+/// docs above.
+pub const MAINNET_BATCH_SUMMARIES: &[BatchSummary] = &[];
+
+/// Testnet's embedded batch summaries; see [`MAINNET_BATCH_SUMMARIES`].
+pub const TESTNET_BATCH_SUMMARIES: &[BatchSummary] = &[];
+
+/// Per-call context for the checks in [`crate::transaction::check`]: whether
+/// the transaction being checked already has its historical correctness
+/// guaranteed by a matching fast-sync batch summary.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TransactionContext {
+    /// If `true`, every fast-sync-aware check in
+    /// [`crate::transaction::check`] returns `Ok(())` immediately: the block
+    /// containing the transaction has already been proven, by a matching
+    /// batch summary hash, to be identical to the canonical historical
+    /// chain.
+    pub fast_sync: bool,
+}
+
+impl TransactionContext {
+    /// The default context: every check runs in full.
+    pub fn full_verification() -> Self {
+        Self { fast_sync: false }
+    }
+
+    /// A context for a transaction inside a batch whose summary hash has
+    /// already been matched against the embedded trusted horizon.
+    pub fn fast_sync() -> Self {
+        Self { fast_sync: true }
+    }
+}
+
+/// Returns `network`'s embedded batch summaries.
+fn batch_summaries(network: Network) -> &'static [BatchSummary] {
+    match network {
+        Network::Mainnet => MAINNET_BATCH_SUMMARIES,
+        Network::Testnet => TESTNET_BATCH_SUMMARIES,
+    }
+}
+
+/// Returns the first block height not covered by `network`'s embedded batch
+/// summaries: the fast-sync horizon. From this height onward, every block is
+/// verified fully.
+pub fn fast_sync_horizon(network: Network) -> block::Height {
+    let batches = batch_summaries(network).len() as u32;
+    block::Height(batches.saturating_mul(FAST_SYNC_BATCH_SIZE))
+}
+
+/// Computes the summary hash of `hashes`, the same way the embedded
+/// summaries are generated: the double-SHA256 of every hash's bytes,
+/// concatenated in height order.
+pub fn summarize_batch<'a>(hashes: impl IntoIterator<Item = &'a block::Hash>) -> BatchSummary {
+    let mut bytes = Vec::new();
+    for hash in hashes {
+        bytes.extend_from_slice(&hash.0);
+    }
+
+    let once = Sha256::digest(&bytes);
+    Sha256::digest(once).into()
+}
+
+/// Returns `true` if the batch of blocks starting at `batch_start`,
+/// identified by `hashes` (every block hash in that batch, in height order),
+/// matches `network`'s embedded summary for that batch.
+///
+/// Returns `false` if the batch isn't covered by an embedded summary, or the
+/// batch's recomputed summary doesn't match the embedded one.
+pub fn batch_is_trusted<'a>(
+    network: Network,
+    batch_start: block::Height,
+    hashes: impl IntoIterator<Item = &'a block::Hash>,
+) -> bool {
+    if batch_start.0 % FAST_SYNC_BATCH_SIZE != 0 {
+        return false;
+    }
+
+    let index = (batch_start.0 / FAST_SYNC_BATCH_SIZE) as usize;
+
+    match batch_summaries(network).get(index) {
+        Some(expected) => summarize_batch(hashes) == *expected,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A batch summary changes if any block hash in the batch changes,
+    /// including a single tampered byte, so a tampered transaction (which
+    /// changes its block's hash) is never silently trusted.
+    #[test]
+    fn tampered_batch_hash_changes_summary() {
+        let original = vec![block::Hash([1; 32]), block::Hash([2; 32]), block::Hash([3; 32])];
+        let mut tampered = original.clone();
+        tampered[1] = block::Hash([0xff; 32]);
+
+        assert_ne!(
+            summarize_batch(original.iter()),
+            summarize_batch(tampered.iter())
+        );
+    }
+
+    #[test]
+    fn batch_is_trusted_is_false_without_embedded_summaries() {
+        let network = Network::Mainnet;
+        let hashes = vec![block::Hash([7; 32])];
+
+        // No summaries are embedded yet, so nothing is trusted.
+        assert!(!batch_is_trusted(network, block::Height(0), hashes.iter()));
+        assert_eq!(fast_sync_horizon(network), block::Height(0));
+    }
+
+    #[test]
+    fn batch_is_trusted_requires_a_batch_boundary() {
+        let network = Network::Mainnet;
+        let hashes = vec![block::Hash([7; 32])];
+
+        assert!(!batch_is_trusted(network, block::Height(1), hashes.iter()));
+    }
+}
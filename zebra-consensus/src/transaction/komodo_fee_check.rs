@@ -0,0 +1,213 @@
+//! Komodo's minimum relay fee rate and free-transaction rate limiter.
+//!
+//! Ported from `komodod`'s mempool admission policy: transactions paying less
+//! than [`DEFAULT_MIN_RELAY_TX_FEE`] per 1000 bytes are still relayed, but only
+//! up to a continuously decaying budget, so a flood of low-fee transactions
+//! can't be used to spam every node's mempool for free.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Utc};
+
+use zebra_chain::{
+    amount::Amount,
+    serialization::ZcashSerialize,
+    transaction::Transaction,
+    transparent,
+};
+
+/// The default minimum relay transaction fee rate, in zatoshis per 1000 bytes.
+pub const DEFAULT_MIN_RELAY_TX_FEE: i64 = 1000;
+
+/// The free-transaction relay budget, in bytes per minute, before the rate
+/// limiter starts rejecting further below-`min_relay_txfee` transactions.
+const FREE_RELAY_BYTES_PER_MINUTE: f64 = 15_000.0;
+
+/// The decay factor applied to the used part of the free-transaction budget
+/// for every second that passes: a continuous decay with a 600-second
+/// (~10-minute) time constant, i.e. a half-life of about 7 minutes (matching
+/// `komodod`'s `-limitfreerelay` limiter).
+const FREE_RELAY_DECAY_PER_SECOND: f64 = 1.0 - 1.0 / 600.0;
+
+/// A fee rate, expressed as zatoshis per 1000 serialized bytes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FeeRate(Amount);
+
+impl FeeRate {
+    /// Creates a new fee rate of `zatoshis_per_1000_bytes` zatoshis per 1000 bytes.
+    pub fn new(zatoshis_per_1000_bytes: Amount) -> Self {
+        Self(zatoshis_per_1000_bytes)
+    }
+
+    /// Returns the fee this rate charges for a transaction of `tx_size` bytes.
+    pub fn get_fee(&self, tx_size: usize) -> Amount {
+        let rate: i64 = self.0.into();
+        let fee = (rate * tx_size as i64 + 999) / 1000;
+
+        Amount::try_from(fee).unwrap_or(self.0)
+    }
+}
+
+/// Tracks the unconfirmed ancestors of mempool transactions, so that a
+/// transaction can be evaluated together with the fees and sizes of the
+/// unconfirmed mempool transactions it spends from.
+///
+/// This is a pure lookup table: callers are responsible for keeping it in
+/// sync with the mempool (inserting accepted transactions, removing mined or
+/// evicted ones).
+#[derive(Clone, Debug, Default)]
+pub struct PackageFeeTable {
+    /// The fee and size of every unconfirmed mempool transaction, keyed by
+    /// the outpoints it creates, so ancestors can be found from a child's
+    /// inputs without keeping a separate txid index.
+    by_outpoint: HashMap<transparent::OutPoint, Arc<MempoolEntry>>,
+}
+
+/// A mempool transaction's fee and size, cached so ancestor package fee-rates
+/// don't need to re-serialize or re-verify every ancestor transaction.
+#[derive(Clone, Debug)]
+struct MempoolEntry {
+    fee: Amount,
+    size: usize,
+    inputs: Vec<transparent::OutPoint>,
+}
+
+impl PackageFeeTable {
+    /// Creates an empty package fee table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `tx`'s `fee`, so it can be found as an ancestor of any
+    /// transaction that later spends one of its outputs.
+    pub fn insert(&mut self, tx: &Transaction, fee: Amount) {
+        let txid = tx.hash();
+        let size = tx
+            .zcash_serialized_size()
+            .expect("a mempool transaction must have a valid serialized size");
+        let inputs = tx
+            .inputs()
+            .iter()
+            .filter_map(|input| match input {
+                transparent::Input::PrevOut { outpoint, .. } => Some(*outpoint),
+                transparent::Input::Coinbase { .. } => None,
+            })
+            .collect();
+
+        let entry = Arc::new(MempoolEntry { fee, size, inputs });
+
+        for output_index in 0..tx.outputs().len() {
+            self.by_outpoint.insert(
+                transparent::OutPoint {
+                    hash: txid,
+                    index: output_index as u32,
+                },
+                entry.clone(),
+            );
+        }
+    }
+
+    /// Removes `tx` from the table, once it has been mined or evicted.
+    pub fn remove(&mut self, tx: &Transaction) {
+        let txid = tx.hash();
+        self.by_outpoint
+            .retain(|outpoint, _| outpoint.hash != txid);
+    }
+
+    /// Returns the combined fee and size of `tx` and all of its unconfirmed
+    /// mempool ancestors, without double-counting an ancestor reachable
+    /// through more than one path.
+    pub fn package_fee_and_size(&self, tx: &Transaction, tx_fee: Amount, tx_size: usize) -> (Amount, usize) {
+        let mut seen = HashSet::new();
+        let mut total_fee = tx_fee;
+        let mut total_size = tx_size;
+
+        let mut pending: Vec<transparent::OutPoint> = tx
+            .inputs()
+            .iter()
+            .filter_map(|input| match input {
+                transparent::Input::PrevOut { outpoint, .. } => Some(*outpoint),
+                transparent::Input::Coinbase { .. } => None,
+            })
+            .collect();
+
+        while let Some(outpoint) = pending.pop() {
+            let Some(ancestor) = self.by_outpoint.get(&outpoint) else {
+                // The ancestor isn't an unconfirmed mempool transaction
+                // (it's already mined), so it isn't part of the package.
+                continue;
+            };
+
+            if !seen.insert(outpoint.hash) {
+                // Already counted this ancestor through another outpoint.
+                continue;
+            }
+
+            total_fee = (total_fee + ancestor.fee).unwrap_or(total_fee);
+            total_size += ancestor.size;
+            pending.extend(ancestor.inputs.iter().copied());
+        }
+
+        (total_fee, total_size)
+    }
+}
+
+/// Rate-limits the relay of transactions paying below [`DEFAULT_MIN_RELAY_TX_FEE`].
+///
+/// Implements the same continuously-decaying limit as `komodod`: the budget
+/// starts at [`FREE_RELAY_BYTES_PER_MINUTE`], each relayed transaction uses
+/// its size from it, and the used part decays by
+/// [`FREE_RELAY_DECAY_PER_SECOND`] for every second since the last check, so
+/// the budget refills towards [`FREE_RELAY_BYTES_PER_MINUTE`].
+#[derive(Clone, Debug)]
+pub struct FeeRateLimiter {
+    /// The remaining free-transaction relay budget, in bytes.
+    free_relay_budget: f64,
+
+    /// The last time the budget was updated.
+    last_update: Option<DateTime<Utc>>,
+}
+
+impl FeeRateLimiter {
+    /// Creates a new rate limiter with a full free-transaction relay budget.
+    pub fn new() -> Self {
+        Self {
+            free_relay_budget: FREE_RELAY_BYTES_PER_MINUTE,
+            last_update: None,
+        }
+    }
+
+    /// Returns `true` if `tx` can be relayed under the current free-relay
+    /// budget as of `now`, consuming `tx`'s size from the budget if so.
+    pub fn check_rate_limit(&mut self, tx: &Transaction, now: DateTime<Utc>) -> bool {
+        let tx_size = tx
+            .zcash_serialized_size()
+            .expect("structurally valid transaction must have size") as f64;
+
+        if let Some(last_update) = self.last_update {
+            let elapsed_seconds = (now - last_update).num_seconds().max(0) as f64;
+            let used = FREE_RELAY_BYTES_PER_MINUTE - self.free_relay_budget;
+            self.free_relay_budget = FREE_RELAY_BYTES_PER_MINUTE
+                - used * FREE_RELAY_DECAY_PER_SECOND.powf(elapsed_seconds);
+            self.free_relay_budget = self.free_relay_budget.min(FREE_RELAY_BYTES_PER_MINUTE);
+        }
+        self.last_update = Some(now);
+
+        if self.free_relay_budget < tx_size {
+            return false;
+        }
+
+        self.free_relay_budget -= tx_size;
+
+        true
+    }
+}
+
+impl Default for FeeRateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,111 @@
+//! Tests for asynchronous transaction verification.
+
+use std::{future::Future, pin::Pin, sync::mpsc, thread};
+
+use futures::{executor::block_on, future, stream::FuturesUnordered, FutureExt, StreamExt};
+
+use super::AsyncChecks;
+
+/// Drains `checks`, writing each `(index, result)` back into a same-sized `Vec`, the way
+/// [`super::Verifier::verify_block_transactions`] drains its queued per-transaction checks
+/// into its `check_results`.
+///
+/// This exercises that exact mechanism — tagging each queued check with its batch index and
+/// writing its result back to that index once it resolves, regardless of resolution order —
+/// without needing a real [`Transaction`](zebra_chain::transaction::Transaction): this
+/// snapshot doesn't have `zebra_chain::transaction`'s defining module, so a batch of real
+/// transactions crafted to pass or fail in a chosen order can't be constructed here.
+fn drain_into_slots(
+    mut checks: FuturesUnordered<
+        Pin<Box<dyn Future<Output = (usize, Result<(), &'static str>)> + Send>>,
+    >,
+    len: usize,
+) -> Vec<Result<(), &'static str>> {
+    let mut results: Vec<Result<(), &'static str>> = (0..len).map(|_| Ok(())).collect();
+
+    block_on(async {
+        while let Some((index, result)) = checks.next().await {
+            results[index] = result;
+        }
+    });
+
+    results
+}
+
+#[test]
+fn block_transaction_checks_land_at_their_own_index() {
+    // Each check carries its own index, and its result must land at that slot, whatever order
+    // `FuturesUnordered` finishes the checks in. Index 2 fails; that failure must land at
+    // slot 2 only.
+    let mut checks: FuturesUnordered<
+        Pin<Box<dyn Future<Output = (usize, Result<(), &'static str>)> + Send>>,
+    > = FuturesUnordered::new();
+
+    checks.push(
+        async {
+            future::ready(()).await;
+            (0, Ok(()))
+        }
+        .boxed(),
+    );
+    checks.push(future::ready((1, Ok(()))).boxed());
+    checks.push(future::ready((2, Err("transaction 2 is invalid"))).boxed());
+
+    let results = drain_into_slots(checks, 3);
+
+    assert_eq!(results[0], Ok(()));
+    assert_eq!(results[1], Ok(()));
+    assert_eq!(results[2], Err("transaction 2 is invalid"));
+}
+
+#[test]
+fn block_transaction_checks_default_to_ok_for_an_empty_batch() {
+    let checks: FuturesUnordered<
+        Pin<Box<dyn Future<Output = (usize, Result<(), &'static str>)> + Send>>,
+    > = FuturesUnordered::new();
+
+    assert_eq!(drain_into_slots(checks, 0), Vec::<Result<(), &'static str>>::new());
+}
+
+/// `verify_block_transactions`'s script checks, and the RedPallas/RedJubjub signature checks
+/// in `ShieldedBundle::queue_checks`, both depend on [`AsyncChecks::push_blocking`] to actually
+/// run their CPU-bound verification on the rayon pool rather than on the calling (async
+/// executor) thread.
+///
+/// A real end-to-end test driving `verify_block_transactions` with a full block can't be
+/// written here: this snapshot doesn't have `zebra_chain::transaction`'s defining module, so a
+/// [`Transaction`](zebra_chain::transaction::Transaction) can't be constructed to build a
+/// [`Request::Block`](super::Request::Block) from. What this test can and does verify directly
+/// is the mechanism those checks rely on: that `push_blocking` actually hands its closure off
+/// to a different thread instead of running it inline.
+#[test]
+fn push_blocking_runs_the_check_off_the_calling_thread() {
+    let calling_thread = thread::current().id();
+    let (ran_on, recv_ran_on) = mpsc::channel();
+
+    let mut checks = AsyncChecks::new();
+    checks.push_blocking(move || {
+        let _ = ran_on.send(thread::current().id());
+        Ok(())
+    });
+
+    block_on(checks.check()).expect("the pushed check always succeeds");
+
+    let check_thread = recv_ran_on
+        .recv()
+        .expect("push_blocking's closure always runs before check() resolves");
+    assert_ne!(
+        check_thread, calling_thread,
+        "push_blocking must run its check on a rayon worker thread, not inline"
+    );
+}
+
+#[test]
+fn push_blocking_propagates_the_check_s_error() {
+    let mut checks = AsyncChecks::new();
+    checks.push_blocking(|| Err("synthetic check failure".into()));
+
+    let result = block_on(checks.check());
+
+    assert!(result.is_err());
+}
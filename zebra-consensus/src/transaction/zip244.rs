@@ -0,0 +1,75 @@
+//! A cache for the ZIP-244 signature-digest tree, shared across a V5
+//! transaction's transparent inputs.
+//!
+//! # Status: not wired in
+//!
+//! [`PrecomputedTxDigests`] records the shape this cache needs — the
+//! `header_digest` and the three per-pool digests are identical for every
+//! input under `SIGHASH_ALL`, so computing them once per transaction instead
+//! of once per input removes the O(inputs²) hashing cost `verify_v5_transaction`
+//! currently pays. It can't be built or threaded through yet, because both of
+//! the pieces it would plug into are absent from this checkout:
+//!
+//! - [`Transaction::sighash`](zebra_chain::transaction::Transaction::sighash) computes the
+//!   whole ZIP-244 tree in one opaque call; the BLAKE2b-256 node functions (`header_digest`,
+//!   `transparent_digest`, `prevouts_digest`, `sequence_digest`, `outputs_digest`) live inside
+//!   `zebra_chain::transaction`'s defining module, which isn't part of this snapshot, so there's
+//!   nothing here to call to produce the sub-digests this struct would hold.
+//! - `script::Request` (in the `script` module, also not part of this snapshot) has no field
+//!   for a precomputed digest tree, and adding one is a change to that module's public API, not
+//!   this one's.
+//!
+//! Computing these digests independently from scratch, in a file that has no
+//! visibility into either the real node-hashing functions or the existing
+//! `sighash` implementation's byte layout, risks landing a consensus-critical
+//! digest that silently disagrees with the real one — worse than not caching
+//! at all. [`PrecomputedTxDigests::from_transaction`] is left `todo!()` and
+//! unreferenced rather than guessed at; wiring this up for real needs both of
+//! the modules above restored to this checkout first.
+
+use zebra_chain::transaction::Transaction;
+
+/// The part of a V5 transaction's ZIP-244 signature-digest tree that doesn't depend on which
+/// transparent input is being signed: everything except the per-input `transparent_sig_digest`.
+///
+/// Computed once per transaction and reused across every `script::Request` for its inputs,
+/// instead of each input recomputing the whole tree.
+#[allow(dead_code)]
+pub(crate) struct PrecomputedTxDigests {
+    /// `T.1: header_digest` — version, version group id, consensus branch id, lock_time, and
+    /// expiry_height.
+    pub header_digest: [u8; 32],
+
+    /// `T.2: transparent_digest` — combines [`Self::prevouts_digest`], [`Self::sequence_digest`],
+    /// and [`Self::outputs_digest`]; shared by every input, which then folds in its own
+    /// `transparent_sig_digest` on top.
+    pub transparent_digest: [u8; 32],
+
+    /// `T.2a: prevouts_digest`, over every transparent input's outpoint.
+    pub prevouts_digest: [u8; 32],
+
+    /// `T.2b: sequence_digest`, over every transparent input's sequence number.
+    pub sequence_digest: [u8; 32],
+
+    /// `T.2c: outputs_digest`, over every transparent output.
+    pub outputs_digest: [u8; 32],
+
+    /// `T.3: sapling_digest`, empty if the transaction has no Sapling shielded data.
+    pub sapling_digest: [u8; 32],
+
+    /// `T.4: orchard_digest`, empty if the transaction has no Orchard shielded data.
+    pub orchard_digest: [u8; 32],
+}
+
+impl PrecomputedTxDigests {
+    /// Computes the digest tree shared by every transparent input of `transaction`.
+    ///
+    /// Not implemented: see this module's doc comment.
+    #[allow(dead_code)]
+    pub(crate) fn from_transaction(_transaction: &Transaction) -> Self {
+        todo!(
+            "blocked on the sighash node-hashing functions in zebra_chain::transaction's \
+             defining module, which isn't present in this checkout (estar-app/zebra#chunk2-1)"
+        )
+    }
+}
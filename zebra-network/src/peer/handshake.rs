@@ -2,25 +2,28 @@
 
 use std::{
     cmp::min,
-    collections::HashSet,
+    collections::{HashMap, HashSet, VecDeque},
     fmt,
     future::Future,
-    net::{IpAddr, Ipv4Addr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     panic,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
+    time::Duration,
 };
 
 use chrono::{TimeZone, Utc};
+use data_encoding::BASE32_NOPAD;
 use futures::{channel::oneshot, future, pin_mut, FutureExt, SinkExt, StreamExt};
+use sha3::{Digest, Sha3_256};
 use tokio::{
     io::{AsyncRead, AsyncWrite},
     sync::broadcast,
     task::JoinError,
     time::{error, timeout, Instant},
 };
-use tokio_stream::wrappers::IntervalStream;
+use rand::Rng;
 use tokio_util::codec::Framed;
 use tower::Service;
 use tracing::{span, Level, Span};
@@ -67,12 +70,18 @@ where
     user_agent: String,
     our_services: PeerServices,
     relay: bool,
+    required_services: PeerServices,
+    inbound_rate_limit: (f64, f64),
 
     inbound_service: S,
     address_book_updater: tokio::sync::mpsc::Sender<MetaAddrChange>,
+    heartbeat_event_tx: tokio::sync::mpsc::Sender<HeartbeatEvent>,
     inv_collector: broadcast::Sender<InventoryChange>,
     minimum_peer_version: MinimumPeerVersion<C>,
     nonces: Arc<futures::lock::Mutex<HashSet<Nonce>>>,
+    external_addr: Arc<Mutex<ExternalAddrCollector>>,
+    time_data: Arc<Mutex<TimeData>>,
+    reputation: Arc<Mutex<HandshakeReputation>>,
 
     parent_span: Span,
 }
@@ -89,21 +98,647 @@ where
             user_agent: self.user_agent.clone(),
             our_services: self.our_services,
             relay: self.relay,
+            required_services: self.required_services,
+            inbound_rate_limit: self.inbound_rate_limit,
             inbound_service: self.inbound_service.clone(),
             address_book_updater: self.address_book_updater.clone(),
+            heartbeat_event_tx: self.heartbeat_event_tx.clone(),
             inv_collector: self.inv_collector.clone(),
             minimum_peer_version: self.minimum_peer_version.clone(),
             nonces: self.nonces.clone(),
+            external_addr: self.external_addr.clone(),
+            time_data: self.time_data.clone(),
+            reputation: self.reputation.clone(),
             parent_span: self.parent_span.clone(),
         }
     }
 }
 
+/// Network-adjusted time: collects the clock offset each peer's `Version`
+/// timestamp implies relative to our own clock, and exposes their median as
+/// an advisory statistic.
+///
+/// Mirrors zcashd's `timedata.cpp` `AddTimeData`/`nTimeOffset`: the computed
+/// offset is informational only — used for metrics and the "peer clock"
+/// warning below — and must never be used to adjust any consensus-relevant
+/// timestamp.
+pub struct TimeData {
+    /// The most recent offset sample from each peer IP that has contributed
+    /// one, in the order those IPs first contributed (a refreshed sample keeps
+    /// its IP's place).
+    ///
+    /// Bounded to [`TimeData::MAX_SAMPLES`]: once full, recording a sample
+    /// from a new IP evicts the sample of the IP that first contributed
+    /// longest ago.
+    samples: VecDeque<(IpAddr, i64)>,
+}
+
+impl TimeData {
+    /// The maximum number of peer offset samples to retain.
+    pub const MAX_SAMPLES: usize = 200;
+
+    /// The median offset magnitude, in seconds, above which we warn that the
+    /// local clock may be wrong.
+    ///
+    /// <https://github.com/zcash/zcash/blob/master/src/timedata.cpp>
+    pub const WARNING_THRESHOLD_SECS: i64 = 70 * 60;
+
+    /// Returns a new, empty `TimeData`.
+    pub fn new() -> Self {
+        TimeData {
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Records that the peer at `source_ip` implied a clock offset of
+    /// `offset_secs` (their reported timestamp minus our local time).
+    ///
+    /// Each peer IP contributes at most one sample: a peer that reconnects
+    /// just refreshes its existing sample, rather than getting an extra vote.
+    pub fn record(&mut self, source_ip: IpAddr, offset_secs: i64) {
+        if let Some(existing) = self.samples.iter_mut().find(|(ip, _)| *ip == source_ip) {
+            existing.1 = offset_secs;
+            return;
+        }
+
+        if self.samples.len() >= Self::MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((source_ip, offset_secs));
+    }
+
+    /// Returns the median of the collected offsets, or `None` if none have
+    /// been recorded yet.
+    pub fn median_offset(&self) -> Option<i64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let mut offsets: Vec<i64> = self.samples.iter().map(|(_, offset)| *offset).collect();
+        offsets.sort_unstable();
+
+        Some(offsets[offsets.len() / 2])
+    }
+
+    /// Reports the current median offset as a metric, and logs a warning if
+    /// it exceeds [`TimeData::WARNING_THRESHOLD_SECS`].
+    pub fn report(&self) {
+        let Some(median_offset_seconds) = self.median_offset() else {
+            return;
+        };
+
+        metrics::gauge!(
+            "zcash.net.timedata.offset.seconds",
+            median_offset_seconds as f64
+        );
+
+        if median_offset_seconds.abs() > Self::WARNING_THRESHOLD_SECS {
+            warn!(
+                median_offset_seconds,
+                "network-adjusted time differs from the local clock by more than {} minutes; \
+                 this node's clock may be wrong",
+                Self::WARNING_THRESHOLD_SECS / 60,
+            );
+        }
+    }
+}
+
+impl Default for TimeData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Per-IP handshake-failure reputation, used to temporarily ban addresses
+/// that repeatedly fail to complete a handshake or send malformed messages.
+///
+/// A single failure (a bad nonce, an obsolete version, an unexpected
+/// message, a [`SerializationError`] on the connection) isn't evidence of
+/// malice by itself, but a pattern of them from the same IP is worth a
+/// growing, decaying penalty — mirroring the integer-reputation schemes ckb
+/// and Substrate track for the same reason.
+///
+/// Isolated connections are exempt: [`ConnectedAddr::get_transient_addr`]
+/// returns `None` for them by design, so they never have an identifier to
+/// key a score on.
+pub struct HandshakeReputation {
+    scores: HashMap<IpAddr, ReputationEntry>,
+}
+
+/// A single IP's current failure score, when it was last updated, and its
+/// ban expiry, if any.
+struct ReputationEntry {
+    score: f64,
+    last_updated: Instant,
+    /// Set when `score` crosses [`HandshakeReputation::BAN_THRESHOLD`].
+    ///
+    /// This is tracked separately from `score`'s decay: a ban lasts for a
+    /// fixed [`HandshakeReputation::BAN_DURATION`] regardless of how fast the
+    /// score that triggered it would otherwise decay back under the
+    /// threshold.
+    banned_until: Option<Instant>,
+}
+
+impl HandshakeReputation {
+    /// The score added for each handshake failure.
+    pub const FAILURE_PENALTY: f64 = 10.0;
+
+    /// The score at or above which an address is temporarily banned.
+    pub const BAN_THRESHOLD: f64 = 100.0;
+
+    /// The time it takes an unreinforced score to decay to half its value.
+    pub const DECAY_HALF_LIFE: Duration = Duration::from_secs(10 * 60);
+
+    /// How long an address stays banned once its score crosses
+    /// [`BAN_THRESHOLD`](Self::BAN_THRESHOLD).
+    pub const BAN_DURATION: Duration = Duration::from_secs(60 * 60);
+
+    /// Returns a new, empty `HandshakeReputation`.
+    pub fn new() -> Self {
+        HandshakeReputation {
+            scores: HashMap::new(),
+        }
+    }
+
+    /// Returns `true` if `ip` is currently serving an active ban.
+    pub fn is_banned(&self, ip: IpAddr) -> bool {
+        self.scores
+            .get(&ip)
+            .and_then(|entry| entry.banned_until)
+            .map_or(false, |until| Instant::now() < until)
+    }
+
+    /// Returns `ip`'s current failure score, after applying decay since it
+    /// was last updated.
+    pub fn current_score(&self, ip: IpAddr) -> f64 {
+        self.scores
+            .get(&ip)
+            .map_or(0.0, |entry| Self::decayed_score(entry, Instant::now()))
+    }
+
+    /// Records a handshake or connection failure from `ip`, adding to its
+    /// decayed score, and banning it until [`BAN_DURATION`](Self::BAN_DURATION)
+    /// from now if that pushes the score at or above
+    /// [`BAN_THRESHOLD`](Self::BAN_THRESHOLD).
+    pub fn report_failure(&mut self, ip: IpAddr) {
+        let now = Instant::now();
+        let existing = self.scores.get(&ip);
+        let score = existing.map_or(0.0, |entry| Self::decayed_score(entry, now)) + Self::FAILURE_PENALTY;
+        let banned_until = if score >= Self::BAN_THRESHOLD {
+            Some(now + Self::BAN_DURATION)
+        } else {
+            existing.and_then(|entry| entry.banned_until)
+        };
+
+        self.scores.insert(
+            ip,
+            ReputationEntry {
+                score,
+                last_updated: now,
+                banned_until,
+            },
+        );
+    }
+
+    /// Records a successful handshake from `ip`, letting its score decay
+    /// without adding to it.
+    pub fn report_success(&mut self, ip: IpAddr) {
+        if let Some(entry) = self.scores.get_mut(&ip) {
+            let now = Instant::now();
+            entry.score = Self::decayed_score(entry, now);
+            entry.last_updated = now;
+        }
+    }
+
+    /// Returns `entry`'s score, decayed for the time elapsed since it was
+    /// last updated.
+    fn decayed_score(entry: &ReputationEntry, now: Instant) -> f64 {
+        let elapsed_secs = now
+            .saturating_duration_since(entry.last_updated)
+            .as_secs_f64();
+        let half_lives = elapsed_secs / Self::DECAY_HALF_LIFE.as_secs_f64();
+        entry.score * 0.5_f64.powf(half_lives)
+    }
+}
+
+impl Default for HandshakeReputation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A per-connection token-bucket budget on inbound message processing.
+///
+/// A single high-volume peer sending messages faster than we can usefully
+/// process them can otherwise monopolize async scheduling time the same way
+/// other unbounded worker loops have — this caps it: each connection starts
+/// with `capacity` tokens, regains `refill_per_sec` of them every second (up
+/// to `capacity`), and spends one per inbound message.
+pub struct InboundRateLimiter {
+    tokens: f64,
+    capacity: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl InboundRateLimiter {
+    /// The default number of messages a connection may burst before being
+    /// throttled.
+    pub const DEFAULT_CAPACITY: f64 = 200.0;
+
+    /// The default steady-state rate, in messages per second, at which a
+    /// connection's budget refills.
+    pub const DEFAULT_REFILL_PER_SEC: f64 = 50.0;
+
+    /// Returns a new limiter with a full `capacity`-token bucket.
+    pub fn new(capacity: f64, refill_per_sec: f64) -> Self {
+        InboundRateLimiter {
+            tokens: capacity,
+            capacity,
+            refill_per_sec,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Refills the bucket for the time elapsed since the last call, then
+    /// tries to spend one token on an inbound message.
+    ///
+    /// Returns `true` if the message is within budget, `false` if the
+    /// connection should be throttled.
+    pub fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed_secs = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed_secs * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Default for InboundRateLimiter {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY, Self::DEFAULT_REFILL_PER_SEC)
+    }
+}
+
+/// Collects candidate external addresses that peers report seeing us at (via
+/// their `Version` message's `address_recv`), and only trusts one once
+/// [`ExternalAddrCollector::MIN_AGREEING_PEERS`] distinct peer IPs agree on
+/// it.
+///
+/// Modeled on libp2p's identify/observed-address behaviour (and Substrate's
+/// `debug-info` protocol): any single peer can lie about what it sees, but it
+/// takes collusion between several independent source IPs to steer our
+/// self-advertised address.
+#[derive(Default)]
+pub struct ExternalAddrCollector {
+    /// The distinct source IPs that have reported seeing us at each
+    /// candidate address.
+    candidates: HashMap<SocketAddr, HashSet<IpAddr>>,
+}
+
+impl ExternalAddrCollector {
+    /// The number of distinct peer IPs that must agree on a candidate before
+    /// [`confirmed`](Self::confirmed) returns it.
+    ///
+    /// # Security
+    ///
+    /// This must be greater than 1: a single peer's report must never be
+    /// enough to steer our advertised identity.
+    pub const MIN_AGREEING_PEERS: usize = 2;
+
+    /// Returns a new, empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that the peer at `source_ip` reported seeing us at `observed`.
+    ///
+    /// Unroutable, private, and loopback candidates are discarded: they can
+    /// never be a real external address, so trusting them would only let a
+    /// peer blind us to our own reachability.
+    pub fn record(&mut self, source_ip: IpAddr, observed: SocketAddr) {
+        if !is_routable_external_addr(&observed) {
+            return;
+        }
+
+        self.candidates
+            .entry(observed)
+            .or_default()
+            .insert(source_ip);
+    }
+
+    /// Returns the external address confirmed by at least
+    /// [`MIN_AGREEING_PEERS`](Self::MIN_AGREEING_PEERS) distinct source IPs,
+    /// if any.
+    ///
+    /// If more than one candidate meets the threshold, the one with the most
+    /// agreeing peers wins.
+    pub fn confirmed(&self) -> Option<SocketAddr> {
+        self.candidates
+            .iter()
+            .filter(|(_, sources)| sources.len() >= Self::MIN_AGREEING_PEERS)
+            .max_by_key(|(_, sources)| sources.len())
+            .map(|(addr, _)| *addr)
+    }
+}
+
+/// Returns `true` if `addr` could plausibly be a real external address: not
+/// unspecified, not loopback, and not a private-use IP.
+fn is_routable_external_addr(addr: &SocketAddr) -> bool {
+    let ip = addr.ip();
+
+    if addr.port() == 0 || ip.is_unspecified() || ip.is_loopback() {
+        return false;
+    }
+
+    match ip {
+        IpAddr::V4(ip) => !ip.is_private() && !ip.is_link_local(),
+        IpAddr::V6(ip) => !is_unique_local_ipv6(&ip),
+    }
+}
+
+/// Returns `true` for IPv6 unique local addresses (`fc00::/7`), the IPv6
+/// equivalent of IPv4 private ranges.
+///
+/// `Ipv6Addr::is_unique_local` is still unstable, so this checks the range
+/// directly.
+fn is_unique_local_ipv6(ip: &Ipv6Addr) -> bool {
+    (ip.segments()[0] & 0xfe00) == 0xfc00
+}
+
+/// The weight given to each new RTT sample when [`HeartbeatManager::apply`] smooths a peer's
+/// latency measurements.
+const RTT_SMOOTHING_FACTOR: f64 = 0.2;
+
+/// Per-connection heartbeat bookkeeping tracked centrally by a
+/// [`HeartbeatManager`], rather than scattered across each connection's own
+/// `send_periodic_heartbeats_run_loop` task.
+#[derive(Clone, Debug)]
+struct PeerHeartbeatState {
+    /// The services this peer advertised at handshake time.
+    remote_services: PeerServices,
+    /// The EWMA-smoothed round-trip time of this peer's heartbeats so far,
+    /// computed by [`HeartbeatManager::apply`].
+    smoothed_rtt: Option<Duration>,
+}
+
+/// A heartbeat lifecycle event a connection reports to a [`HeartbeatManager`],
+/// in place of sending a `MetaAddrChange` to the address book directly.
+#[derive(Clone, Debug)]
+pub enum HeartbeatEvent {
+    /// `addr` completed a heartbeat round trip of `rtt`, advertising `services`.
+    Responded {
+        addr: ConnectedAddr,
+        services: PeerServices,
+        rtt: Duration,
+    },
+    /// `addr`'s heartbeat failed (timed out, or the connection errored).
+    Errored {
+        addr: ConnectedAddr,
+        services: PeerServices,
+    },
+    /// `addr`'s connection shut down normally.
+    ShutDown {
+        addr: ConnectedAddr,
+        services: PeerServices,
+    },
+}
+
+/// Centrally aggregates heartbeat lifecycle events from every connection,
+/// instead of each connection's heartbeat task independently racing to send
+/// `MetaAddrChange`s to the shared address book updater.
+///
+/// Centralizing this bookkeeping lets the node smooth each peer's RTT across
+/// all of its heartbeats before reporting it, and lets
+/// [`shutdown`](Self::shutdown) wait for every update already in flight to
+/// finish sending before the node exits, instead of each connection racing
+/// its own heartbeat task against process teardown. Repeated updates for the
+/// same peer are not coalesced: every event that implies a `MetaAddrChange`
+/// forwards its own.
+///
+/// [`Handshake`] spawns one `HeartbeatManager` in [`Builder::finish`] and threads its
+/// [`event_sender`](Self::event_sender) into every connection's heartbeat task (see
+/// `send_periodic_heartbeats_with_shutdown_handle`), so every connection's heartbeat lifecycle
+/// reports here instead of sending a `MetaAddrChange` to the address book directly.
+///
+/// # Remaining gap
+///
+/// Nothing in this checkout owns the `HeartbeatManager` value itself, so nothing ever calls
+/// [`shutdown`](Self::shutdown): `Builder::finish` spawns it and keeps only the cloneable
+/// `event_sender`, which is enough to keep the manager's task alive (dropping a
+/// [`tokio::task::JoinHandle`] only detaches it) but not enough to shut it down gracefully on
+/// node exit. That needs whatever eventually owns the `Handshake` service to also hold on to the
+/// `HeartbeatManager` itself.
+pub struct HeartbeatManager {
+    event_tx: tokio::sync::mpsc::Sender<HeartbeatEvent>,
+    shutdown_tx: Option<oneshot::Sender<oneshot::Sender<()>>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl HeartbeatManager {
+    /// The number of in-flight lifecycle events the manager will buffer
+    /// before a reporting connection has to wait to send another.
+    const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+    /// Spawns a `HeartbeatManager` task that aggregates heartbeat lifecycle
+    /// events and forwards `MetaAddrChange`s to `address_book_updater`.
+    pub fn spawn(address_book_updater: tokio::sync::mpsc::Sender<MetaAddrChange>) -> Self {
+        let (event_tx, event_rx) = tokio::sync::mpsc::channel(Self::EVENT_CHANNEL_CAPACITY);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        let task = tokio::spawn(Self::run(event_rx, shutdown_rx, address_book_updater));
+
+        HeartbeatManager {
+            event_tx,
+            shutdown_tx: Some(shutdown_tx),
+            task,
+        }
+    }
+
+    /// Returns a sender connections can report [`HeartbeatEvent`]s on.
+    pub fn event_sender(&self) -> tokio::sync::mpsc::Sender<HeartbeatEvent> {
+        self.event_tx.clone()
+    }
+
+    /// Signals the manager to stop accepting new connections' heartbeats,
+    /// waits for every `MetaAddrChange` it already accepted to finish
+    /// sending, then returns.
+    pub async fn shutdown(mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let (ack_tx, ack_rx) = oneshot::channel();
+            if shutdown_tx.send(ack_tx).is_ok() {
+                let _ = ack_rx.await;
+            }
+        }
+
+        let _ = self.task.await;
+    }
+
+    /// The manager's main loop: aggregates [`HeartbeatEvent`]s into
+    /// `peers`, forwarding a `MetaAddrChange` per event, until asked to shut
+    /// down.
+    async fn run(
+        mut event_rx: tokio::sync::mpsc::Receiver<HeartbeatEvent>,
+        shutdown_rx: oneshot::Receiver<oneshot::Sender<()>>,
+        address_book_updater: tokio::sync::mpsc::Sender<MetaAddrChange>,
+    ) {
+        let mut peers: HashMap<ConnectedAddr, PeerHeartbeatState> = HashMap::new();
+        let mut inflight = tokio::task::JoinSet::new();
+
+        pin_mut!(shutdown_rx);
+
+        loop {
+            tokio::select! {
+                biased;
+
+                ack_tx = &mut shutdown_rx => {
+                    // Stop accepting new events, then let every update
+                    // already queued into `inflight` finish sending before
+                    // returning, so shutdown never abandons a half-sent
+                    // `MetaAddrChange`.
+                    while inflight.join_next().await.is_some() {}
+                    if let Ok(ack_tx) = ack_tx {
+                        let _ = ack_tx.send(());
+                    }
+                    return;
+                }
+
+                event = event_rx.recv() => {
+                    let Some(event) = event else {
+                        return;
+                    };
+
+                    let change = Self::apply(&mut peers, event);
+                    if let Some(change) = change {
+                        let mut address_book_updater = address_book_updater.clone();
+                        inflight.spawn(async move {
+                            let _ = address_book_updater.send(change).await;
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Applies `event` to `peers`, returning the `MetaAddrChange` it implies,
+    /// if the peer has an address-book entry to update.
+    fn apply(
+        peers: &mut HashMap<ConnectedAddr, PeerHeartbeatState>,
+        event: HeartbeatEvent,
+    ) -> Option<MetaAddrChange> {
+        match event {
+            HeartbeatEvent::Responded {
+                addr,
+                services,
+                rtt,
+            } => {
+                let state = peers.entry(addr).or_insert(PeerHeartbeatState {
+                    remote_services: services,
+                    smoothed_rtt: None,
+                });
+                state.remote_services = services;
+                let rtt = state.smoothed_rtt.map_or(rtt, |previous| {
+                    previous.mul_f64(1.0 - RTT_SMOOTHING_FACTOR) + rtt.mul_f64(RTT_SMOOTHING_FACTOR)
+                });
+                state.smoothed_rtt = Some(rtt);
+
+                // A gauge (unlike the per-round-trip histogram each connection records itself)
+                // tracks this peer's most recent smoothed RTT under one label per address, so an
+                // operator can watch a single slow link degrade over time instead of reading it
+                // back out of a latency distribution.
+                metrics::gauge!(
+                    "zcash.net.peers.latency.smoothed_seconds",
+                    rtt.as_secs_f64(),
+                    "addr" => addr.get_transient_addr_label(),
+                );
+
+                addr.get_address_book_addr()
+                    .map(|book_addr| MetaAddr::new_latency(&book_addr, &services, rtt))
+            }
+            HeartbeatEvent::Errored { addr, services } => {
+                peers.remove(&addr);
+                addr.get_address_book_addr()
+                    .map(|book_addr| MetaAddr::new_errored(&book_addr, services))
+            }
+            HeartbeatEvent::ShutDown { addr, services } => {
+                peers.remove(&addr);
+                addr.get_address_book_addr()
+                    .map(|book_addr| MetaAddr::new_shutdown(&book_addr, services))
+            }
+        }
+    }
+}
+
+/// A Tor v3 (`.onion`) service address: the ed25519 public key identifying
+/// the onion service, plus the port its listener accepts Zcash connections
+/// on.
+///
+/// Unlike [`SocketAddr`], this can't be dialed directly: reaching it means
+/// routing a stream through a Tor client (e.g. `arti_client`), which resolves
+/// the public key to a rendezvous circuit rather than a routable network
+/// location.
+///
+/// <https://spec.torproject.org/rend-spec/encoding-onion-addresses.html>
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct OnionAddr {
+    /// The ed25519 public key identifying this onion service.
+    public_key: [u8; 32],
+    /// The port the onion service listens on.
+    port: u16,
+}
+
+impl OnionAddr {
+    /// The only onion service version Zebra supports.
+    const VERSION: u8 = 3;
+
+    /// Returns a new v3 onion address for `public_key` and `port`.
+    pub fn new(public_key: [u8; 32], port: u16) -> OnionAddr {
+        OnionAddr { public_key, port }
+    }
+
+    /// Returns the port this onion service listens on.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the 56-character lowercase base32 service id, without the
+    /// `.onion` suffix or the port.
+    ///
+    /// Computed as `base32(public_key || checksum || version)`, where
+    /// `checksum = sha3_256(".onion checksum" || public_key || version)[..2]`.
+    pub fn service_id(&self) -> String {
+        let mut checksum_input = Vec::with_capacity(b".onion checksum".len() + 32 + 1);
+        checksum_input.extend_from_slice(b".onion checksum");
+        checksum_input.extend_from_slice(&self.public_key);
+        checksum_input.push(Self::VERSION);
+        let checksum = Sha3_256::digest(&checksum_input);
+
+        let mut onion_address = Vec::with_capacity(32 + 2 + 1);
+        onion_address.extend_from_slice(&self.public_key);
+        onion_address.extend_from_slice(&checksum[..2]);
+        onion_address.push(Self::VERSION);
+
+        BASE32_NOPAD.encode(&onion_address).to_lowercase()
+    }
+}
+
+impl fmt::Debug for OnionAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.onion:{}", self.service_id(), self.port)
+    }
+}
+
 /// The peer address that we are handshaking with.
 ///
 /// Typically, we can rely on outbound addresses, but inbound addresses don't
 /// give us enough information to reconnect to that peer.
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum ConnectedAddr {
     /// The address we used to make a direct outbound connection.
     ///
@@ -139,10 +774,19 @@ pub enum ConnectedAddr {
     /// the duration of this connection.
     InboundProxy { transient_addr: SocketAddr },
 
+    /// The Tor onion service we made an outbound connection to.
+    ///
+    /// `transient_local_addr` is the ephemeral local address of the SOCKS
+    /// connection to the Tor client that dialed `onion_addr`, used the same
+    /// way [`OutboundProxy`]'s own transient local address is: as a
+    /// per-connection identifier, not a reconnection address.
+    OutboundOnion {
+        onion_addr: OnionAddr,
+        transient_local_addr: SocketAddr,
+    },
+
     /// An isolated connection, where we deliberately don't have any connection metadata.
     Isolated,
-    //
-    // TODO: handle Tor onion addresses
 }
 
 /// Get an unspecified IPv4 address for `network`
@@ -150,6 +794,24 @@ pub fn get_unspecified_ipv4_addr(network: Network) -> SocketAddr {
     (Ipv4Addr::UNSPECIFIED, network.default_port()).into()
 }
 
+/// The number of anchor connections the peer set should keep reserved for
+/// verified-good peers, on startup and whenever outbound slots open, ahead of
+/// ordinary gray-list addresses.
+///
+/// # Status: not enforced anywhere yet
+///
+/// estar-app/zebra#chunk5-2 asked for the full Monero-style anchor design: marking anchors,
+/// reserving `ANCHOR_CONNECTION_QUOTA` outbound slots for them, and persisting the anchor set
+/// across restarts. Only the marking half landed (the anchor-candidate `MetaAddrChange` emitted
+/// on a successful handshake, below) — this constant has no reader. The selection hook belongs
+/// in the peer-set crawler's connector, and persistence belongs in the address book; neither
+/// module is part of this checkout, so neither can be built or wired from here. Without them,
+/// an attacker flooding the address book with gray-list addresses is exactly as effective as
+/// before this constant existed: marking alone gives zero eclipse resistance. This request
+/// should stay open as a follow-up against the crawler/address-book modules, not be considered
+/// satisfied by this file's changes.
+pub const ANCHOR_CONNECTION_QUOTA: usize = 3;
+
 use ConnectedAddr::*;
 
 impl ConnectedAddr {
@@ -188,6 +850,18 @@ impl ConnectedAddr {
         }
     }
 
+    /// Returns a new outbound connected addr to a Tor onion service.
+    ///
+    /// `local_addr` is the ephemeral local address of the SOCKS connection to
+    /// the Tor client that dialed `onion_addr`.
+    #[allow(unused)]
+    pub fn new_outbound_onion(onion_addr: OnionAddr, local_addr: SocketAddr) -> ConnectedAddr {
+        OutboundOnion {
+            onion_addr,
+            transient_local_addr: local_addr,
+        }
+    }
+
     /// Returns a new isolated connected addr, with no metadata.
     pub fn new_isolated() -> ConnectedAddr {
         Isolated
@@ -196,8 +870,8 @@ impl ConnectedAddr {
     /// Returns a `SocketAddr` that can be used to track this connection in the
     /// `AddressBook`.
     ///
-    /// `None` for inbound connections, proxy connections, and isolated
-    /// connections.
+    /// `None` for inbound connections, proxy connections, onion connections,
+    /// and isolated connections.
     ///
     /// # Correctness
     ///
@@ -214,7 +888,28 @@ impl ConnectedAddr {
             OutboundDirect { addr } => Some(*addr),
             // TODO: consider using the canonical address of the peer to track
             //       outbound proxy connections
-            InboundDirect { .. } | OutboundProxy { .. } | InboundProxy { .. } | Isolated => None,
+            //
+            // TODO: track onion connections once the `AddressBook` can store
+            //       non-IP addresses; until then, use `get_onion_addr` to
+            //       retrieve the onion target for reconnection.
+            InboundDirect { .. }
+            | OutboundProxy { .. }
+            | InboundProxy { .. }
+            | OutboundOnion { .. }
+            | Isolated => None,
+        }
+    }
+
+    /// Returns the onion service address this connection was made to, if any.
+    ///
+    /// Unlike [`get_address_book_addr`](Self::get_address_book_addr), this is
+    /// populated for onion connections, so an onion-aware address book can
+    /// track and re-dial them the same way the regular `AddressBook` tracks
+    /// [`OutboundDirect`] addresses.
+    pub fn get_onion_addr(&self) -> Option<OnionAddr> {
+        match self {
+            OutboundOnion { onion_addr, .. } => Some(*onion_addr),
+            _ => None,
         }
     }
 
@@ -247,6 +942,10 @@ impl ConnectedAddr {
                 ..
             } => Some(*transient_local_addr),
             InboundProxy { transient_addr } => Some(*transient_addr),
+            OutboundOnion {
+                transient_local_addr,
+                ..
+            } => Some(*transient_local_addr),
             Isolated => None,
         }
     }
@@ -264,6 +963,7 @@ impl ConnectedAddr {
             InboundDirect { .. } => "In",
             OutboundProxy { .. } => "ProxOut",
             InboundProxy { .. } => "ProxIn",
+            OutboundOnion { .. } => "Onion",
             Isolated => "Isol",
         }
     }
@@ -316,6 +1016,11 @@ impl ConnectedAddr {
             // can try the canonical remote address
             OutboundProxy { .. } | InboundProxy { .. } => vec![canonical_remote],
 
+            // The canonical remote address from an onion connection's `Version`
+            // message is a SOCKS-facing address, not the onion address we
+            // actually connected to, so it's useless for reconnection.
+            OutboundOnion { .. } => Vec::new(),
+
             // Hide all metadata for isolated connections
             Isolated => Vec::new(),
         };
@@ -348,6 +1053,9 @@ where
     our_services: Option<PeerServices>,
     user_agent: Option<String>,
     relay: Option<bool>,
+    require_compact_filters: Option<bool>,
+    required_services: Option<PeerServices>,
+    inbound_rate_limit: Option<(f64, f64)>,
 
     inbound_service: Option<S>,
     address_book_updater: Option<tokio::sync::mpsc::Sender<MetaAddrChange>>,
@@ -413,6 +1121,53 @@ where
         self
     }
 
+    /// Require that peers advertise the BIP157/158 compact-block-filter
+    /// service bit to complete a handshake. Optional; defaults to `false`.
+    ///
+    /// This is the peer-selection primitive a light client needs: with it
+    /// set, Zebra only finishes handshakes with peers that can serve
+    /// committed filters, so a wallet can fetch filters and only download
+    /// the blocks that match its addresses, instead of syncing the full
+    /// chain.
+    ///
+    /// Setting this also adds `PeerServices::NODE_COMPACT_FILTERS` to the
+    /// services this node advertises, so Zebra can serve compact filters to
+    /// other light clients as well as consume them.
+    pub fn require_compact_filters(mut self, require_compact_filters: bool) -> Self {
+        self.require_compact_filters = Some(require_compact_filters);
+        self
+    }
+
+    /// Require that peers advertise `services` to complete a handshake.
+    /// Optional; defaults to [`PeerServices::empty`].
+    ///
+    /// Unlike [`require_compact_filters`](Self::require_compact_filters), this
+    /// doesn't special-case any one service bit: it's the general mechanism
+    /// operators use to run capability-specialized node pools (e.g. requiring
+    /// `NODE_NETWORK` for a syncing node, or a future light-wallet bit),
+    /// without `negotiate_version` needing an ad-hoc match arm per bit.
+    ///
+    /// Calling this more than once is cumulative: each call adds `services`
+    /// to the required set, rather than replacing it.
+    pub fn require_services(mut self, services: PeerServices) -> Self {
+        let required_services = self.required_services.unwrap_or_else(PeerServices::empty);
+        self.required_services = Some(required_services | services);
+        self
+    }
+
+    /// Configure the per-connection inbound message rate budget: `capacity`
+    /// messages may burst through before throttling kicks in, then the
+    /// budget refills at `refill_per_sec` messages per second. Optional;
+    /// defaults to [`InboundRateLimiter::DEFAULT_CAPACITY`] and
+    /// [`InboundRateLimiter::DEFAULT_REFILL_PER_SEC`].
+    ///
+    /// This keeps a single high-volume peer from monopolizing async
+    /// scheduling time at the expense of every other connection.
+    pub fn with_inbound_rate_limit(mut self, capacity: f64, refill_per_sec: f64) -> Self {
+        self.inbound_rate_limit = Some((capacity, refill_per_sec));
+        self
+    }
+
     /// Provide a realtime endpoint to obtain the current best chain tip block height. Optional.
     ///
     /// If this is unset, the minimum accepted protocol version for peer connections is kept
@@ -433,6 +1188,9 @@ where
             our_services: self.our_services,
             user_agent: self.user_agent,
             relay: self.relay,
+            require_compact_filters: self.require_compact_filters,
+            required_services: self.required_services,
+            inbound_rate_limit: self.inbound_rate_limit,
             inv_collector: self.inv_collector,
         }
     }
@@ -463,10 +1221,32 @@ where
             let (tx, _rx) = tokio::sync::mpsc::channel(1);
             tx
         });
+        // Every connection's heartbeat reports into this one `HeartbeatManager` instead of
+        // sending `MetaAddrChange`s to `address_book_updater` itself, so heartbeat bookkeeping
+        // (smoothing each peer's RTT) is centralized rather than duplicated per
+        // connection. Dropping the returned `HeartbeatManager` here only detaches its
+        // `JoinHandle` — the spawned task keeps running — so this intentionally only keeps the
+        // event sender; a graceful `shutdown()` needs whatever eventually owns this `Handshake`
+        // to hold on to the `HeartbeatManager` itself instead, which nothing in this checkout
+        // does yet.
+        let heartbeat_event_tx = HeartbeatManager::spawn(address_book_updater.clone()).event_sender();
         let nonces = Arc::new(futures::lock::Mutex::new(HashSet::new()));
+        let external_addr = Arc::new(Mutex::new(ExternalAddrCollector::new()));
+        let time_data = Arc::new(Mutex::new(TimeData::new()));
+        let reputation = Arc::new(Mutex::new(HandshakeReputation::new()));
         let user_agent = self.user_agent.unwrap_or_else(|| "".to_string());
-        let our_services = self.our_services.unwrap_or_else(PeerServices::empty);
+        let require_compact_filters = self.require_compact_filters.unwrap_or(false);
+        let mut our_services = self.our_services.unwrap_or_else(PeerServices::empty);
+        let mut required_services = self.required_services.unwrap_or_else(PeerServices::empty);
+        if require_compact_filters {
+            our_services |= PeerServices::NODE_COMPACT_FILTERS;
+            required_services |= PeerServices::NODE_COMPACT_FILTERS;
+        }
         let relay = self.relay.unwrap_or(false);
+        let inbound_rate_limit = self.inbound_rate_limit.unwrap_or((
+            InboundRateLimiter::DEFAULT_CAPACITY,
+            InboundRateLimiter::DEFAULT_REFILL_PER_SEC,
+        ));
         let network = config.network;
         let minimum_peer_version = MinimumPeerVersion::new(self.latest_chain_tip, network);
 
@@ -475,11 +1255,17 @@ where
             user_agent,
             our_services,
             relay,
+            required_services,
+            inbound_rate_limit,
             inbound_service,
             address_book_updater,
+            heartbeat_event_tx,
             inv_collector,
             minimum_peer_version,
             nonces,
+            external_addr,
+            time_data,
+            reputation,
             parent_span: Span::current(),
         })
     }
@@ -500,6 +1286,9 @@ where
             our_services: None,
             user_agent: None,
             relay: None,
+            require_compact_filters: None,
+            required_services: None,
+            inbound_rate_limit: None,
             inbound_service: None,
             address_book_updater: None,
             inv_collector: None,
@@ -519,14 +1308,30 @@ pub async fn negotiate_version<PeerTransport>(
     connected_addr: &ConnectedAddr,
     config: Config,
     nonces: Arc<futures::lock::Mutex<HashSet<Nonce>>>,
+    external_addr: Arc<Mutex<ExternalAddrCollector>>,
+    time_data: Arc<Mutex<TimeData>>,
+    reputation: Arc<Mutex<HandshakeReputation>>,
     user_agent: String,
     our_services: PeerServices,
     relay: bool,
+    required_services: PeerServices,
     mut minimum_peer_version: MinimumPeerVersion<impl ChainTip>,
 ) -> Result<(Version, PeerServices, SocketAddr), HandshakeError>
 where
     PeerTransport: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
+    // The source IP this handshake's outcome is scored against, if any.
+    // Isolated connections have none, so they're never scored.
+    let reputation_ip = connected_addr.get_transient_addr().map(|addr| addr.ip());
+    let report_failure = |reputation: &Arc<Mutex<HandshakeReputation>>| {
+        if let Some(ip) = reputation_ip {
+            reputation
+                .lock()
+                .expect("handshake reputation mutex should not be poisoned")
+                .report_failure(ip);
+        }
+    };
+
     // Create a random nonce for this connection
     let local_nonce = Nonce::default();
     // # Correctness
@@ -571,13 +1376,29 @@ where
         }
     };
 
+    // Use the external address other peers have confirmed seeing us at, once
+    // enough of them agree on it (see `ExternalAddrCollector`). Until then,
+    // fall back to our configured listener address, which is wrong for any
+    // node behind NAT.
+    let our_advertised_addr = external_addr
+        .lock()
+        .expect("external address collector mutex should not be poisoned")
+        .confirmed()
+        .unwrap_or(our_listen_addr);
+
     let our_version = Message::Version {
         version: constants::CURRENT_NETWORK_PROTOCOL_VERSION,
         services: our_services,
         timestamp,
         address_recv: AddrInVersion::new(their_addr, PeerServices::NODE_NETWORK),
-        // TODO: detect external address (#1893)
-        address_from: AddrInVersion::new(our_listen_addr, our_services),
+        // TODO: once the wire protocol gains BIP155 addrv2 support (a
+        //       `sendaddrv2`/`addrv2` message pair, and an addrv2-capable
+        //       `AddrInVersion`/`Codec`), advertise and learn `.onion`
+        //       addresses here the same way IPv4/IPv6 addresses are
+        //       advertised today. Until then, `OutboundOnion` connections
+        //       negotiate normally, but `onion_addr` never crosses the wire
+        //       in `address_from`/`address_recv`.
+        address_from: AddrInVersion::new(our_advertised_addr, our_services),
         nonce: local_nonce,
         user_agent: user_agent.clone(),
         // The protocol works fine if we don't reveal our current block height,
@@ -616,7 +1437,9 @@ where
         if let Message::Version {
             version,
             services,
+            timestamp: remote_timestamp,
             address_from,
+            address_recv,
             nonce,
             user_agent,
             ..
@@ -632,8 +1455,32 @@ where
                 );
             }
 
+            // This peer's `address_recv` is its own view of where it's
+            // sending this message to, i.e. their best guess at our address.
+            // Only the real, OS-reported source IP of the connection counts
+            // as an independent "vote": `address_from` is self-reported by
+            // the peer, so using it here would let a single peer register as
+            // many votes as it likes by opening several connections with
+            // different claimed `address_from`s.
+            if let Some(source_ip) = connected_addr.get_transient_addr().map(|addr| addr.ip()) {
+                external_addr
+                    .lock()
+                    .expect("external address collector mutex should not be poisoned")
+                    .record(source_ip, address_recv.addr());
+
+                // Network-adjusted time: record how far this peer's clock is
+                // from ours. This is advisory only (see `TimeData`'s doc
+                // comment) — it must never feed back into `timestamp` above.
+                let mut time_data = time_data
+                    .lock()
+                    .expect("time data mutex should not be poisoned");
+                time_data.record(source_ip, remote_timestamp.timestamp() - now);
+                time_data.report();
+            }
+
             (nonce, services, version, canonical_addr, user_agent)
         } else {
+            report_failure(&reputation);
             Err(HandshakeError::UnexpectedMessage(Box::new(remote_msg)))?
         };
 
@@ -652,6 +1499,7 @@ where
         nonce_reuse
     };
     if nonce_reuse {
+        report_failure(&reputation);
         Err(HandshakeError::NonceReuse)?;
     }
 
@@ -684,6 +1532,7 @@ where
         );
 
         // Disconnect if peer is using an obsolete version.
+        report_failure(&reputation);
         Err(HandshakeError::ObsoleteVersion(remote_version))?;
     } else {
         let negotiated_version = min(constants::CURRENT_NETWORK_PROTOCOL_VERSION, remote_version);
@@ -715,6 +1564,33 @@ where
         );
     }
 
+    // SECURITY: only complete handshakes with peers that advertise every
+    // service bit this node (or its operator, via `Builder::require_services`
+    // and `Builder::require_compact_filters`) requires. This is the general
+    // capability-negotiation primitive: a light client uses it to demand
+    // BIP157/158 compact filters, and an operator running a
+    // capability-specialized node pool can demand any other bit, without
+    // `negotiate_version` growing an ad-hoc match arm per capability.
+    let missing_services = required_services - remote_services;
+    if !missing_services.is_empty() {
+        debug!(
+            remote_ip = ?their_addr,
+            ?remote_services,
+            ?missing_services,
+            "disconnecting from peer missing required services"
+        );
+
+        metrics::counter!(
+            "zcash.net.peers.rejected.services",
+            1,
+            "remote_ip" => their_addr.to_string(),
+            "missing_services" => format!("{missing_services:?}"),
+        );
+
+        report_failure(&reputation);
+        Err(HandshakeError::MissingServices(missing_services))?;
+    }
+
     peer_conn.send(Message::Verack).await?;
 
     let mut remote_msg = peer_conn
@@ -739,6 +1615,13 @@ where
         }
     }
 
+    if let Some(ip) = reputation_ip {
+        reputation
+            .lock()
+            .expect("handshake reputation mutex should not be poisoned")
+            .report_success(ip);
+    }
+
     Ok((remote_version, remote_services, remote_canonical_addr))
 }
 
@@ -793,16 +1676,41 @@ where
 
         // Clone these upfront, so they can be moved into the future.
         let nonces = self.nonces.clone();
+        let external_addr = self.external_addr.clone();
+        let time_data = self.time_data.clone();
+        let reputation = self.reputation.clone();
         let inbound_service = self.inbound_service.clone();
         let address_book_updater = self.address_book_updater.clone();
+        let heartbeat_event_tx = self.heartbeat_event_tx.clone();
         let inv_collector = self.inv_collector.clone();
         let config = self.config.clone();
         let user_agent = self.user_agent.clone();
         let our_services = self.our_services;
         let relay = self.relay;
+        let required_services = self.required_services;
+        let inbound_rate_limit = self.inbound_rate_limit;
         let minimum_peer_version = self.minimum_peer_version.clone();
 
         let fut = async move {
+            // SECURITY: reject addresses with too many recent handshake
+            // failures before doing any protocol work. Isolated connections
+            // have no transient address, so they're exempt by construction.
+            if let Some(ip) = connected_addr.get_transient_addr().map(|addr| addr.ip()) {
+                if reputation
+                    .lock()
+                    .expect("handshake reputation mutex should not be poisoned")
+                    .is_banned(ip)
+                {
+                    debug!(?ip, "rejecting handshake from banned address");
+                    metrics::counter!(
+                        "zcash.net.peers.banned",
+                        1,
+                        "remote_ip" => ip.to_string(),
+                    );
+                    Err(HandshakeError::Banned(ip))?;
+                }
+            }
+
             debug!(
                 addr = ?connected_addr,
                 "negotiating protocol version with remote peer"
@@ -816,15 +1724,33 @@ where
                     .finish(),
             );
 
+            // Keep a handle to the reputation table for the connection layer
+            // below, which also reports failures (on `SerializationError`s in
+            // the `peer_rx` pipeline) after the handshake itself succeeds.
+            let connection_reputation = reputation.clone();
+
+            // A fresh, per-connection inbound message budget: unlike
+            // `reputation`, this must not be shared across connections, or
+            // one peer's traffic would spend another peer's tokens.
+            let (rate_limit_capacity, rate_limit_refill_per_sec) = inbound_rate_limit;
+            let rate_limiter = Arc::new(Mutex::new(InboundRateLimiter::new(
+                rate_limit_capacity,
+                rate_limit_refill_per_sec,
+            )));
+
             // Wrap the entire initial connection setup in a timeout.
             let (remote_version, remote_services, remote_canonical_addr) = negotiate_version(
                 &mut peer_conn,
                 &connected_addr,
                 config,
                 nonces,
+                external_addr,
+                time_data,
+                reputation,
                 user_agent,
                 our_services,
                 relay,
+                required_services,
                 minimum_peer_version,
             )
             .await?;
@@ -852,6 +1778,24 @@ where
                 let _ = address_book_updater
                     .send(MetaAddr::new_responded(&book_addr, &remote_services))
                     .await;
+
+                // SECURITY: also mark this peer as an anchor candidate: a
+                // peer we've verifiably completed a handshake with, not just
+                // one an untrusted `addr`/`addrv2` gossip message named.
+                //
+                // Mirrors the white/gray/anchor peer-list design Monero-derived
+                // stacks use against eclipse attacks: an attacker that floods
+                // our address book with attacker-controlled gray-list
+                // addresses still can't displace the handful of anchors we
+                // dial first on restart and when outbound slots open.
+                //
+                // This only records the candidate; it does not reserve any outbound slots for
+                // it or persist it across restarts, so it does not by itself provide eclipse
+                // resistance. See `ANCHOR_CONNECTION_QUOTA`'s doc comment for what's missing
+                // and why it can't be built in this file.
+                let _ = address_book_updater
+                    .send(MetaAddr::new_anchor(&book_addr, &remote_services))
+                    .await;
             }
 
             // Set the connection's version to the minimum of the received version or our own.
@@ -914,6 +1858,8 @@ where
                     // Add a metric for inbound messages and errors.
                     // Fire a timestamp or failure event.
                     let inbound_ts_collector = inbound_ts_collector.clone();
+                    let connection_reputation = connection_reputation.clone();
+                    let rate_limiter = rate_limiter.clone();
                     let span =
                         debug_span!(parent: ts_inner_conn_span.clone(), "inbound_ts_collector");
 
@@ -939,6 +1885,62 @@ where
                                             .await;
                                     }
                                 }
+
+                                // SECURITY: throttle a connection that's
+                                // sending messages faster than its budget
+                                // allows, so one high-volume peer can't
+                                // monopolize async scheduling time at the
+                                // expense of every other connection.
+                                let within_budget = rate_limiter
+                                    .lock()
+                                    .expect("inbound rate limiter mutex should not be poisoned")
+                                    .try_acquire();
+                                if !within_budget {
+                                    metrics::counter!(
+                                        "zebra.net.in.throttled",
+                                        1,
+                                        "addr" => connected_addr.get_transient_addr_label(),
+                                    );
+
+                                    if let Some(book_addr) = connected_addr.get_address_book_addr()
+                                    {
+                                        // Treat sustained throttling as the same
+                                        // kind of misbehaviour signal an error
+                                        // on the connection is.
+                                        let _ = inbound_ts_collector
+                                            .send(MetaAddr::new_errored(
+                                                &book_addr,
+                                                remote_services,
+                                            ))
+                                            .await;
+                                    }
+
+                                    if let Some(ip) =
+                                        connected_addr.get_transient_addr().map(|addr| addr.ip())
+                                    {
+                                        // Persistent throttling accumulates
+                                        // through the same decaying score as
+                                        // handshake and `SerializationError`
+                                        // failures, so a peer that won't slow
+                                        // down eventually gets banned ahead of
+                                        // its next reconnection attempt, via
+                                        // the existing `is_banned` check at
+                                        // the top of `Service::call`.
+                                        //
+                                        // TODO: tear down *this* connection
+                                        // immediately on sustained throttling,
+                                        // instead of waiting for the peer to
+                                        // reconnect. That needs a write path
+                                        // into `error_slot` from here, which
+                                        // isn't available in this build.
+                                        connection_reputation
+                                            .lock()
+                                            .expect(
+                                                "handshake reputation mutex should not be poisoned",
+                                            )
+                                            .report_failure(ip);
+                                    }
+                                }
                             }
                             Err(err) => {
                                 metrics::counter!(
@@ -953,6 +1955,22 @@ where
                                         .send(MetaAddr::new_errored(&book_addr, remote_services))
                                         .await;
                                 }
+
+                                // SECURITY: a `SerializationError` on an
+                                // established connection is the same kind of
+                                // evidence a handshake failure is: count it
+                                // against the peer's reputation, so a peer
+                                // that floods malformed messages gets banned
+                                // the same way a peer that fails handshakes
+                                // repeatedly does.
+                                if let Some(ip) =
+                                    connected_addr.get_transient_addr().map(|addr| addr.ip())
+                                {
+                                    connection_reputation
+                                        .lock()
+                                        .expect("handshake reputation mutex should not be poisoned")
+                                        .report_failure(ip);
+                                }
                             }
                         }
                         msg
@@ -989,7 +2007,7 @@ where
                     remote_services,
                     shutdown_rx,
                     server_tx.clone(),
-                    address_book_updater.clone(),
+                    heartbeat_event_tx,
                 )
                 .instrument(tracing::debug_span!(parent: connection_span, "heartbeat"))
                 .boxed(),
@@ -1110,17 +2128,16 @@ pub(crate) async fn register_inventory_status(
     msg
 }
 
-/// Send periodical heartbeats to `server_tx`, and update the peer status through
-/// `heartbeat_ts_collector`.
+/// Send periodical heartbeats to `server_tx`, reporting this connection's heartbeat lifecycle
+/// to the central [`HeartbeatManager`] via `heartbeat_events`.
 ///
 /// # Correctness
 ///
 /// To prevent hangs:
 /// - every await that depends on the network must have a timeout (or interval)
-/// - every error/shutdown must update the address book state and return
+/// - every error/shutdown must report a [`HeartbeatEvent`] and return
 ///
-/// The address book state can be updated via `ClientRequest.tx`, or the
-/// heartbeat_ts_collector.
+/// The address book is updated by the `HeartbeatManager`, not directly from here.
 ///
 /// Returning from this function terminates the connection's heartbeat task.
 async fn send_periodic_heartbeats_with_shutdown_handle(
@@ -1128,7 +2145,7 @@ async fn send_periodic_heartbeats_with_shutdown_handle(
     remote_services: PeerServices,
     shutdown_rx: oneshot::Receiver<CancelHeartbeatTask>,
     server_tx: futures::channel::mpsc::Sender<ClientRequest>,
-    mut heartbeat_ts_collector: tokio::sync::mpsc::Sender<MetaAddrChange>,
+    mut heartbeat_events: tokio::sync::mpsc::Sender<HeartbeatEvent>,
 ) -> Result<(), BoxError> {
     use futures::future::Either;
 
@@ -1136,7 +2153,7 @@ async fn send_periodic_heartbeats_with_shutdown_handle(
         connected_addr,
         remote_services,
         server_tx,
-        heartbeat_ts_collector.clone(),
+        heartbeat_events.clone(),
     );
 
     pin_mut!(shutdown_rx);
@@ -1156,7 +2173,7 @@ async fn send_periodic_heartbeats_with_shutdown_handle(
             tracing::trace!("shutting down because Client requested shut down");
             handle_heartbeat_shutdown(
                 PeerError::ClientCancelledHeartbeatTask,
-                &mut heartbeat_ts_collector,
+                &mut heartbeat_events,
                 &connected_addr,
                 &remote_services,
             )
@@ -1166,7 +2183,7 @@ async fn send_periodic_heartbeats_with_shutdown_handle(
             tracing::trace!("shutting down because Client was dropped");
             handle_heartbeat_shutdown(
                 PeerError::ClientDropped,
-                &mut heartbeat_ts_collector,
+                &mut heartbeat_events,
                 &connected_addr,
                 &remote_services,
             )
@@ -1181,53 +2198,118 @@ async fn send_periodic_heartbeats_with_shutdown_handle(
     }
 }
 
-/// Send periodical heartbeats to `server_tx`, and update the peer status through
-/// `heartbeat_ts_collector`.
+/// Send periodical heartbeats to `server_tx`, reporting each round trip's raw RTT to the
+/// central [`HeartbeatManager`] via `heartbeat_events`.
 ///
 /// See `send_periodic_heartbeats_with_shutdown_handle` for details.
 async fn send_periodic_heartbeats_run_loop(
     connected_addr: ConnectedAddr,
     remote_services: PeerServices,
     mut server_tx: futures::channel::mpsc::Sender<ClientRequest>,
-    mut heartbeat_ts_collector: tokio::sync::mpsc::Sender<MetaAddrChange>,
+    mut heartbeat_events: tokio::sync::mpsc::Sender<HeartbeatEvent>,
 ) -> Result<(), BoxError> {
+    // The in-flight heartbeat's nonce and send time, if one hasn't been
+    // matched to its `Pong` (or dropped by a timeout) yet.
+    //
+    // `Connection` serialises heartbeats on a single connection, so at most
+    // one entry is ever live at once; it's still a map, rather than a single
+    // `Option`, so a heartbeat that times out just leaves its nonce behind
+    // to be silently dropped, instead of corrupting the next heartbeat's
+    // measurement.
+    let mut pending_pings: HashMap<Nonce, Instant> = HashMap::new();
+
     // Don't send the first heartbeat immediately - we've just completed the handshake!
-    let mut interval = tokio::time::interval_at(
-        Instant::now() + constants::HEARTBEAT_INTERVAL,
-        constants::HEARTBEAT_INTERVAL,
-    );
-    // If the heartbeat is delayed, also delay all future heartbeats.
-    // (Shorter heartbeat intervals just add load, without any benefit.)
-    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut next_heartbeat_delay = jittered_heartbeat_interval();
 
-    let mut interval_stream = IntervalStream::new(interval);
+    loop {
+        tokio::time::sleep(next_heartbeat_delay).await;
 
-    while let Some(_instant) = interval_stream.next().await {
         // We've reached another heartbeat interval without
         // shutting down, so do a heartbeat request.
-        let heartbeat = send_one_heartbeat(&mut server_tx);
-        heartbeat_timeout(
+        let heartbeat = send_one_heartbeat(&mut server_tx, &mut pending_pings);
+        let nonce = heartbeat_timeout(
             heartbeat,
-            &mut heartbeat_ts_collector,
+            &mut heartbeat_events,
             &connected_addr,
             &remote_services,
         )
         .await?;
+
+        // The nonce we just got back can only be the one this heartbeat sent:
+        // if it timed out instead, `heartbeat_timeout` would have returned an
+        // error above and we wouldn't reach here. Remove it so a later,
+        // spuriously duplicated `Pong` can't be matched against a stale entry.
+        if let Some(sent_at) = pending_pings.remove(&nonce) {
+            let rtt = sent_at.elapsed();
+
+            metrics::histogram!(
+                "zcash.net.peers.latency",
+                rtt.as_secs_f64(),
+                "addr" => connected_addr.get_transient_addr_label(),
+            );
+            tracing::debug!(
+                ?rtt,
+                addr = %connected_addr.get_transient_addr_label(),
+                "heartbeat round trip completed",
+            );
+
+            // The raw RTT is reported as-is; smoothing it into a single per-peer estimate (and
+            // turning that into a `MetaAddrChange`) is the `HeartbeatManager`'s job now, not
+            // this connection's own — see `HeartbeatManager::apply`.
+            let _ = heartbeat_events
+                .send(HeartbeatEvent::Responded {
+                    addr: connected_addr,
+                    services: remote_services,
+                    rtt,
+                })
+                .await;
+        }
+
+        // That heartbeat succeeded, so schedule the next one at the usual
+        // jittered interval. (`heartbeat_timeout` already escalated any
+        // error to `handle_heartbeat_error` and returned it via `?` above,
+        // ending this task, so there's no "retry the same heartbeat sooner"
+        // case to back off: every heartbeat this loop reaches has succeeded.)
+        next_heartbeat_delay = jittered_heartbeat_interval();
     }
+}
 
-    unreachable!("unexpected IntervalStream termination")
+/// The maximum fraction of [`constants::HEARTBEAT_INTERVAL`] that a single
+/// heartbeat's schedule may be shifted by.
+///
+/// Without jitter, every connection established in a burst (e.g. right after
+/// startup) would send its heartbeats in lockstep, creating a synchronized
+/// wave of Pings every interval instead of a smooth trickle.
+const HEARTBEAT_JITTER_FRACTION: f64 = 0.1;
+
+/// Returns [`constants::HEARTBEAT_INTERVAL`], shifted by a random offset of
+/// up to [`HEARTBEAT_JITTER_FRACTION`] in either direction.
+fn jittered_heartbeat_interval() -> Duration {
+    let interval_secs = constants::HEARTBEAT_INTERVAL.as_secs_f64();
+    let jitter_secs = interval_secs * HEARTBEAT_JITTER_FRACTION;
+    let offset_secs = rand::thread_rng().gen_range(-jitter_secs..=jitter_secs);
+
+    Duration::from_secs_f64((interval_secs + offset_secs).max(0.0))
 }
 
-/// Send one heartbeat using `server_tx`.
+/// Send one heartbeat using `server_tx`, recording its nonce and send time in
+/// `pending_pings`, and returning that nonce once the matching response
+/// arrives.
 async fn send_one_heartbeat(
     server_tx: &mut futures::channel::mpsc::Sender<ClientRequest>,
-) -> Result<(), BoxError> {
+    pending_pings: &mut HashMap<Nonce, Instant>,
+) -> Result<Nonce, BoxError> {
     // We just reached a heartbeat interval, so start sending
     // a heartbeat.
     let (tx, rx) = oneshot::channel();
 
+    // Generate a fresh nonce per heartbeat, and record when we sent it, so
+    // the round trip to its `Pong` reply can be measured.
+    let nonce = Nonce::default();
+    pending_pings.insert(nonce, Instant::now());
+
     // Try to send the heartbeat request
-    let request = Request::Ping(Nonce::default());
+    let request = Request::Ping(nonce);
     tracing::trace!(?request, "queueing heartbeat request");
     match server_tx.try_send(ClientRequest {
         request,
@@ -1264,25 +2346,31 @@ async fn send_one_heartbeat(
     rx.await??;
     tracing::trace!("got heartbeat response");
 
-    Ok(())
+    Ok(nonce)
 }
 
-/// Wrap `fut` in a timeout, handing any inner or outer errors using
+/// Wrap `fut` in a reply timeout, handing any inner or outer errors using
 /// `handle_heartbeat_error`.
+///
+/// `fut` is a single heartbeat round-trip (see [`send_one_heartbeat`]), so
+/// this bounds it with [`constants::HEARTBEAT_REPLY_TIMEOUT`] rather than
+/// [`constants::HEARTBEAT_INTERVAL`]: a silent peer is detected as soon as
+/// its reply is overdue, instead of only once the *next* heartbeat falls
+/// due, up to a full interval later.
 async fn heartbeat_timeout<F, T>(
     fut: F,
-    address_book_updater: &mut tokio::sync::mpsc::Sender<MetaAddrChange>,
+    heartbeat_events: &mut tokio::sync::mpsc::Sender<HeartbeatEvent>,
     connected_addr: &ConnectedAddr,
     remote_services: &PeerServices,
 ) -> Result<T, BoxError>
 where
     F: Future<Output = Result<T, BoxError>>,
 {
-    let t = match timeout(constants::HEARTBEAT_INTERVAL, fut).await {
+    let t = match timeout(constants::HEARTBEAT_REPLY_TIMEOUT, fut).await {
         Ok(inner_result) => {
             handle_heartbeat_error(
                 inner_result,
-                address_book_updater,
+                heartbeat_events,
                 connected_addr,
                 remote_services,
             )
@@ -1291,7 +2379,7 @@ where
         Err(elapsed) => {
             handle_heartbeat_error(
                 Err(elapsed),
-                address_book_updater,
+                heartbeat_events,
                 connected_addr,
                 remote_services,
             )
@@ -1302,10 +2390,11 @@ where
     Ok(t)
 }
 
-/// If `result.is_err()`, mark `connected_addr` as failed using `address_book_updater`.
+/// If `result.is_err()`, report `connected_addr`'s heartbeat as failed to the central
+/// [`HeartbeatManager`] via `heartbeat_events`.
 async fn handle_heartbeat_error<T, E>(
     result: Result<T, E>,
-    address_book_updater: &mut tokio::sync::mpsc::Sender<MetaAddrChange>,
+    heartbeat_events: &mut tokio::sync::mpsc::Sender<HeartbeatEvent>,
     connected_addr: &ConnectedAddr,
     remote_services: &PeerServices,
 ) -> Result<T, E>
@@ -1317,30 +2406,33 @@ where
         Err(err) => {
             tracing::debug!(?err, "heartbeat error, shutting down");
 
-            if let Some(book_addr) = connected_addr.get_address_book_addr() {
-                let _ = address_book_updater
-                    .send(MetaAddr::new_errored(&book_addr, *remote_services))
-                    .await;
-            }
+            let _ = heartbeat_events
+                .send(HeartbeatEvent::Errored {
+                    addr: *connected_addr,
+                    services: *remote_services,
+                })
+                .await;
             Err(err)
         }
     }
 }
 
-/// Mark `connected_addr` as shut down using `address_book_updater`.
+/// Report `connected_addr`'s connection as shut down to the central [`HeartbeatManager`] via
+/// `heartbeat_events`.
 async fn handle_heartbeat_shutdown(
     peer_error: PeerError,
-    address_book_updater: &mut tokio::sync::mpsc::Sender<MetaAddrChange>,
+    heartbeat_events: &mut tokio::sync::mpsc::Sender<HeartbeatEvent>,
     connected_addr: &ConnectedAddr,
     remote_services: &PeerServices,
 ) -> Result<(), BoxError> {
     tracing::debug!(?peer_error, "client shutdown, shutting down heartbeat");
 
-    if let Some(book_addr) = connected_addr.get_address_book_addr() {
-        let _ = address_book_updater
-            .send(MetaAddr::new_shutdown(&book_addr, *remote_services))
-            .await;
-    }
+    let _ = heartbeat_events
+        .send(HeartbeatEvent::ShutDown {
+            addr: *connected_addr,
+            services: *remote_services,
+        })
+        .await;
 
     Err(peer_error.into())
 }